@@ -0,0 +1,149 @@
+//! Async counterpart to `MemoryStore`'s JSONL persistence, built on
+//! `tokio::fs`/`tokio::io::AsyncWriteExt` so writes don't block the
+//! warp/tokio runtime the way `MemoryStore::save_all_memories`/
+//! `add_memory_and_save` do. Gated behind the `async-memory` feature so the
+//! synchronous API keeps compiling unchanged for callers that don't opt
+//! in — the sync/async split common to embedded-store crates.
+
+use crate::types::Memory;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration};
+
+/// Flush the write-behind buffer once it holds this many pending memories...
+const FLUSH_QUEUE_DEPTH: usize = 32;
+/// ...or this long has passed since the last flush, whichever comes first.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Async, non-blocking JSONL persistence for `Memory` records. `add_memory`
+/// queues onto a background writer that coalesces rapid calls into a single
+/// buffered append; `append_memory`/`save_all`/`load_from_jsonl` are async
+/// and write/read directly for callers that need the result immediately.
+pub struct AsyncMemoryStore {
+    file_path: String,
+    pending: mpsc::UnboundedSender<Memory>,
+}
+
+impl AsyncMemoryStore {
+    /// Open `file_path` for the background writer and spawn it. Dropping
+    /// the returned store closes the channel, which flushes any buffered
+    /// memories before the writer task exits.
+    pub fn new(file_path: String) -> Self {
+        let (pending, rx) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run_writer(file_path.clone(), rx));
+        Self { file_path, pending }
+    }
+
+    /// Queue `memory` for the background writer rather than blocking the
+    /// caller on a write. Coalesces with whatever else is queued in the
+    /// same flush window.
+    pub fn add_memory(&self, memory: Memory) {
+        // Unbounded send only fails if the writer task has already exited
+        // (e.g. during shutdown); there's nothing useful to do but drop it.
+        let _ = self.pending.send(memory);
+    }
+
+    /// Append one memory immediately, bypassing the background writer, for
+    /// callers that need the write durable before returning.
+    pub async fn append_memory(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        Self::append_all(&self.file_path, std::slice::from_ref(memory)).await
+    }
+
+    /// Overwrite the file with exactly `memories`, same semantics as
+    /// `MemoryStore::save_all_memories`.
+    pub async fn save_all(&self, memories: &[Memory]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = tokio::fs::File::create(&self.file_path).await?;
+        for memory in memories {
+            write_line(&mut file, memory).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Load every memory from `file_path` without blocking the runtime.
+    pub async fn load_from_jsonl(file_path: &str) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let contents = match tokio::fs::read_to_string(file_path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut memories = Vec::new();
+        for line in contents.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            memories.push(serde_json::from_str(line)?);
+        }
+        Ok(memories)
+    }
+
+    async fn append_all(file_path: &str, memories: &[Memory]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .await?;
+        for memory in memories {
+            write_line(&mut file, memory).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Background task: batch queued memories and flush them in one append
+    /// once `FLUSH_QUEUE_DEPTH` memories have piled up or `FLUSH_INTERVAL`
+    /// has elapsed, whichever comes first.
+    async fn run_writer(file_path: String, mut rx: mpsc::UnboundedReceiver<Memory>) {
+        let mut buffer = Vec::new();
+        let mut ticker = interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                maybe_memory = rx.recv() => {
+                    match maybe_memory {
+                        Some(memory) => {
+                            buffer.push(memory);
+                            if buffer.len() >= FLUSH_QUEUE_DEPTH {
+                                Self::flush(&file_path, &mut buffer).await;
+                            }
+                        }
+                        None => {
+                            Self::flush(&file_path, &mut buffer).await;
+                            return;
+                        }
+                    }
+                }
+                _ = ticker.tick() => {
+                    Self::flush(&file_path, &mut buffer).await;
+                }
+            }
+        }
+    }
+
+    async fn flush(file_path: &str, buffer: &mut Vec<Memory>) {
+        if buffer.is_empty() {
+            return;
+        }
+        if let Err(e) = Self::append_all(file_path, buffer).await {
+            tracing::error!(
+                "Async memory writer failed to flush {} memories to {}: {}",
+                buffer.len(),
+                file_path,
+                e
+            );
+        }
+        buffer.clear();
+    }
+}
+
+async fn write_line(
+    file: &mut (impl AsyncWriteExt + Unpin),
+    memory: &Memory,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let line = serde_json::to_string(memory)?;
+    file.write_all(line.as_bytes()).await?;
+    file.write_all(b"\n").await?;
+    Ok(())
+}
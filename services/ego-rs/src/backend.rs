@@ -0,0 +1,365 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::Client;
+use serde_json::json;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A live sequence of text chunks from a model response, used by the
+/// `/api/ego/reflect/stream` SSE route to forward progress as it arrives.
+pub type TokenStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Transport + protocol for talking to a language model. `ReflectionEngine`
+/// builds prompts and parses responses; everything provider-specific lives
+/// behind this trait so switching models never touches prompt construction.
+#[async_trait]
+pub trait ReflectionBackend: Send + Sync {
+    /// Send `prompt` to the model and return the completed text.
+    async fn generate(&self, prompt: &str) -> Result<String>;
+
+    /// Like `generate`, but yields incremental chunks as they arrive.
+    /// Backends without native streaming support fall back to yielding the
+    /// complete response as a single chunk.
+    async fn generate_stream(&self, prompt: &str) -> Result<TokenStream> {
+        let text = self.generate(prompt).await?;
+        Ok(Box::pin(stream::once(async move { Ok(text) })))
+    }
+
+    /// Cheap reachability check used by the `/api/ego/status` endpoint.
+    async fn health(&self) -> Result<bool>;
+
+    /// Model identifier to surface in responses/logs.
+    fn model(&self) -> &str;
+}
+
+/// Ollama's `/api/generate` endpoint, consuming its JSON-lines stream format.
+pub struct OllamaBackend {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaBackend {
+    pub fn new(base_url: String, model: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            client,
+            base_url,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl ReflectionBackend for OllamaBackend {
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let request_body = json!({
+            "model": self.model,
+            "prompt": prompt,
+            "options": {
+                "temperature": 0.2,
+                "top_p": 0.9,
+                "repeat_penalty": 1.1
+            },
+            "stream": true
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama API error: {}", response.status());
+        }
+
+        let text = response.text().await?;
+        let mut result = String::new();
+        for line in text.trim().split('\n') {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+                if let Some(response_text) = json.get("response").and_then(|v| v.as_str()) {
+                    result.push_str(response_text);
+                }
+            }
+        }
+
+        Ok(result.trim().to_string())
+    }
+
+    async fn generate_stream(&self, prompt: &str) -> Result<TokenStream> {
+        let request_body = json!({
+            "model": self.model,
+            "prompt": prompt,
+            "options": {
+                "temperature": 0.2,
+                "top_p": 0.9,
+                "repeat_penalty": 1.1
+            },
+            "stream": true
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama API error: {}", response.status());
+        }
+
+        // Ollama's stream is newline-delimited JSON objects; each chunk we
+        // get from reqwest may carry one or several complete lines.
+        let tokens = response.bytes_stream().flat_map(|chunk| {
+            let lines: Vec<Result<String>> = match chunk {
+                Ok(bytes) => String::from_utf8_lossy(&bytes)
+                    .lines()
+                    .filter_map(|line| {
+                        serde_json::from_str::<serde_json::Value>(line)
+                            .ok()?
+                            .get("response")
+                            .and_then(|v| v.as_str())
+                            .map(|s| Ok(s.to_string()))
+                    })
+                    .collect(),
+                Err(e) => vec![Err(anyhow::Error::from(e))],
+            };
+            stream::iter(lines)
+        });
+
+        Ok(Box::pin(tokens))
+    }
+
+    async fn health(&self) -> Result<bool> {
+        let response = self
+            .client
+            .get(format!("{}/api/tags", self.base_url))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await;
+
+        match response {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+/// OpenAI's `/v1/chat/completions` protocol.
+pub struct OpenAiBackend {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiBackend {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            client,
+            base_url,
+            api_key,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl ReflectionBackend for OpenAiBackend {
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let request_body = json!({
+            "model": self.model,
+            "messages": [{"role": "user", "content": prompt}],
+            "temperature": 0.2,
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("OpenAI API error: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let content = body["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("OpenAI response missing message content"))?;
+
+        Ok(content.trim().to_string())
+    }
+
+    async fn health(&self) -> Result<bool> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .bearer_auth(&self.api_key)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await;
+
+        match response {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+/// Any server that speaks the OpenAI chat-completions protocol (vLLM,
+/// text-generation-inference, LM Studio, etc.) without requiring an API key.
+pub struct OpenAiCompatibleBackend {
+    inner: OpenAiBackend,
+}
+
+impl OpenAiCompatibleBackend {
+    pub fn new(base_url: String, api_key: Option<String>, model: String) -> Self {
+        Self {
+            inner: OpenAiBackend::new(base_url, api_key.unwrap_or_default(), model),
+        }
+    }
+}
+
+#[async_trait]
+impl ReflectionBackend for OpenAiCompatibleBackend {
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.inner.generate(prompt).await
+    }
+
+    async fn health(&self) -> Result<bool> {
+        self.inner.health().await
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+}
+
+/// Probe `{base_url}/api/tags` for the names of models Ollama actually has
+/// pulled. Errors (unreachable daemon, bad JSON) surface as `Err` so the
+/// caller can fall back without mistaking "couldn't ask" for "nothing
+/// installed".
+async fn installed_ollama_models(base_url: &str) -> Result<Vec<String>> {
+    let response = Client::new()
+        .get(format!("{}/api/tags", base_url))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Ollama /api/tags returned {}", response.status());
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    let names = body["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(names)
+}
+
+/// Try each of `candidates` in order against the models Ollama reports as
+/// installed, tolerating the `:latest`/tag suffix Ollama appends to bare
+/// names. Falls back to the first candidate (and lets `generate` surface
+/// the real error) if the daemon can't be reached or none match, so a
+/// transient probe failure never blocks startup.
+async fn resolve_ollama_model(base_url: &str, candidates: &[String]) -> String {
+    let fallback = candidates
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "llama3.2:3b".to_string());
+
+    let installed = match installed_ollama_models(base_url).await {
+        Ok(installed) => installed,
+        Err(e) => {
+            tracing::warn!(
+                "Could not probe Ollama for installed models ({}); defaulting to {}",
+                e,
+                fallback
+            );
+            return fallback;
+        }
+    };
+
+    for candidate in candidates {
+        let latest = format!("{}:latest", candidate);
+        if installed
+            .iter()
+            .any(|name| name == candidate || (!candidate.contains(':') && *name == latest))
+        {
+            tracing::info!("Selected Ollama model \"{}\" from preference chain", candidate);
+            return candidate.clone();
+        }
+    }
+
+    tracing::warn!(
+        "None of {:?} are installed in Ollama; defaulting to {}",
+        candidates,
+        fallback
+    );
+    fallback
+}
+
+/// Build a backend from `Config`'s `provider` field. For `provider =
+/// "ollama"`, walks `config.models` in preference order and selects the
+/// first one actually pulled in the local Ollama instance, so a missing
+/// top-choice model degrades gracefully instead of failing every reflection.
+pub async fn from_config(config: &crate::config::Config) -> Box<dyn ReflectionBackend> {
+    let first_choice = config
+        .models
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "llama3.2:3b".to_string());
+
+    match config.provider.as_str() {
+        "openai" => Box::new(OpenAiBackend::new(
+            config
+                .api_base
+                .clone()
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            config.api_key.clone().unwrap_or_default(),
+            first_choice,
+        )),
+        "openai_compatible" => Box::new(OpenAiCompatibleBackend::new(
+            config
+                .api_base
+                .clone()
+                .unwrap_or_else(|| config.ollama_url.clone()),
+            config.api_key.clone(),
+            first_choice,
+        )),
+        _ => {
+            let model = resolve_ollama_model(&config.ollama_url, &config.models).await;
+            Box::new(OllamaBackend::new(config.ollama_url.clone(), model))
+        }
+    }
+}
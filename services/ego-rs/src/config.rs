@@ -1,14 +1,143 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub port: u16,
     pub ollama_url: String,
-    pub model: String,
+    /// Ordered model preference chain, tried in order until one is
+    /// available. Accepts either a single string or a list under either the
+    /// `models` or (for back-compat with older configs) `model` key.
+    #[serde(alias = "model", deserialize_with = "string_or_vec", default = "default_models")]
+    pub models: Vec<String>,
     pub temperature: f32,
     pub top_p: f32,
     pub max_memories: usize,
     pub time_window_minutes: u64,
+    /// Which `ReflectionBackend` to build: "ollama" (default), "openai", or
+    /// "openai_compatible" for any self-hosted OpenAI-protocol server.
+    #[serde(default = "default_provider")]
+    pub provider: String,
+    /// Base URL for the `openai`/`openai_compatible` providers. Falls back to
+    /// `ollama_url` for `openai_compatible` when unset.
+    #[serde(default)]
+    pub api_base: Option<String>,
+    /// API key for the `openai` provider.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Which `EmbeddingProvider` to build: "ollama" (default) or "openai".
+    #[serde(default = "default_embedding_provider")]
+    pub embedding_provider: String,
+    /// Embedding model name passed to the provider.
+    #[serde(default = "default_embedding_model")]
+    pub embedding_model: String,
+    /// Which `MemoryBackend` to build: "jsonl" (default), "sqlite", "sled",
+    /// or "oplog".
+    #[serde(default = "default_memory_backend")]
+    pub memory_backend: String,
+    /// Database file (sqlite) or directory (sled) used by the non-JSONL
+    /// memory backends.
+    #[serde(default = "default_memory_db_path")]
+    pub memory_db_path: String,
+    /// Operation log file used by the `oplog` memory backend; its
+    /// checkpoint is written alongside it at `<memory_log_path>.checkpoint`.
+    #[serde(default = "default_memory_log_path")]
+    pub memory_log_path: String,
+    /// "plaintext" (default) or "encrypted". When "encrypted", STM/LTM
+    /// JSONL records are sealed with `encryption_key` via
+    /// XChaCha20-Poly1305 before being written to disk.
+    #[serde(default = "default_persistence_mode")]
+    pub persistence_mode: String,
+    /// 64 hex character (32-byte) symmetric key used when
+    /// `persistence_mode = "encrypted"`.
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+    /// Upper bound on tool-call round-trips per reflection before the model
+    /// is forced to answer with whatever context it has gathered so far.
+    #[serde(default = "default_max_tool_steps")]
+    pub max_tool_steps: usize,
+    /// OTLP endpoint (e.g. `http://localhost:4317`) to export traces and
+    /// metrics to. Unset means telemetry is a no-op: `opentelemetry`'s
+    /// default meter/tracer providers silently discard everything recorded
+    /// against them.
+    #[serde(default)]
+    pub otel_exporter_endpoint: Option<String>,
+}
+
+/// Accept either a bare string (`model = "llama3.2:3b"`) or a list
+/// (`models = ["llama3.2:3b", "llama3.2:1b"]`) for the same field.
+fn string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StringOrVec;
+
+    impl<'de> serde::de::Visitor<'de> for StringOrVec {
+        type Value = Vec<String>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a string or a list of strings")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Vec<String>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(vec![v.to_string()])
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<String>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut models = Vec::new();
+            while let Some(model) = seq.next_element::<String>()? {
+                models.push(model);
+            }
+            Ok(models)
+        }
+    }
+
+    deserializer.deserialize_any(StringOrVec)
+}
+
+fn default_models() -> Vec<String> {
+    vec![
+        "llama3.2:3b".to_string(),
+        "llama3.2:1b".to_string(),
+        "llama3.2".to_string(),
+    ]
+}
+
+fn default_provider() -> String {
+    "ollama".to_string()
+}
+
+fn default_embedding_provider() -> String {
+    "ollama".to_string()
+}
+
+fn default_embedding_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_memory_backend() -> String {
+    "jsonl".to_string()
+}
+
+fn default_memory_db_path() -> String {
+    "data/memory.db".to_string()
+}
+
+fn default_memory_log_path() -> String {
+    "data/stm.oplog".to_string()
+}
+
+fn default_persistence_mode() -> String {
+    "plaintext".to_string()
+}
+
+fn default_max_tool_steps() -> usize {
+    5
 }
 
 impl Default for Config {
@@ -16,11 +145,86 @@ impl Default for Config {
         Self {
             port: 8084,
             ollama_url: "http://localhost:11434".to_string(),
-            model: "llama3.2:3b".to_string(),
+            models: default_models(),
             temperature: 0.2,
             top_p: 0.9,
             max_memories: 24,
             time_window_minutes: 20,
+            provider: default_provider(),
+            api_base: None,
+            api_key: None,
+            embedding_provider: default_embedding_provider(),
+            embedding_model: default_embedding_model(),
+            memory_backend: default_memory_backend(),
+            memory_db_path: default_memory_db_path(),
+            memory_log_path: default_memory_log_path(),
+            persistence_mode: default_persistence_mode(),
+            encryption_key: None,
+            max_tool_steps: default_max_tool_steps(),
+            otel_exporter_endpoint: None,
+        }
+    }
+}
+
+impl Config {
+    /// Layer configuration lowest-to-highest precedence: `Default`, an
+    /// optional `config.toml` in the working directory, then `LJ_*`
+    /// environment variables. Lets operators reconfigure a deployment
+    /// without a rebuild.
+    pub fn load() -> Self {
+        let mut config = Self::from_toml_file("config.toml").unwrap_or_default();
+        config.apply_env_overrides();
+        config
+    }
+
+    fn from_toml_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        match toml::from_str(&contents) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::warn!("Failed to parse {}: {}; using defaults", path, e);
+                None
+            }
+        }
+    }
+
+    fn apply_env_overrides(&mut self) {
+        if let Ok(v) = std::env::var("LJ_PORT") {
+            match v.parse() {
+                Ok(port) => self.port = port,
+                Err(e) => tracing::warn!("Ignoring invalid LJ_PORT={:?}: {}", v, e),
+            }
+        }
+        if let Ok(v) = std::env::var("LJ_OLLAMA_URL") {
+            self.ollama_url = v;
+        }
+        if let Ok(v) = std::env::var("LJ_MODEL") {
+            self.models = v
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Ok(v) = std::env::var("LJ_MAX_MEMORIES") {
+            match v.parse() {
+                Ok(n) => self.max_memories = n,
+                Err(e) => tracing::warn!("Ignoring invalid LJ_MAX_MEMORIES={:?}: {}", v, e),
+            }
+        }
+        if let Ok(v) = std::env::var("LJ_TIME_WINDOW_MINUTES") {
+            match v.parse() {
+                Ok(n) => self.time_window_minutes = n,
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid LJ_TIME_WINDOW_MINUTES={:?}: {}", v, e)
+                }
+            }
+        }
+        if let Ok(v) = std::env::var("LJ_OTEL_EXPORTER_ENDPOINT") {
+            self.otel_exporter_endpoint = Some(v);
+        }
+        if let Ok(v) = std::env::var("LJ_ENCRYPTION_KEY") {
+            self.encryption_key = Some(v);
         }
     }
 }
@@ -158,6 +158,7 @@ impl ConsolidationEngine {
             importance,
             context_hash,
             tags: vec!["consolidated".to_string(), "experience".to_string()],
+            embedding: vec![],
         })
     }
 
@@ -371,7 +372,7 @@ impl ConsolidationEngine {
                 content
             );
 
-            match reflection_engine.call_ollama(&prompt).await {
+            match reflection_engine.generate(&prompt).await {
                 Ok(response) => {
                     let themes: Vec<String> = response
                         .split(',')
@@ -400,7 +401,7 @@ impl ConsolidationEngine {
                 content
             );
 
-            match reflection_engine.call_ollama(&prompt).await {
+            match reflection_engine.generate(&prompt).await {
                 Ok(response) => Ok(response.trim().to_string()),
                 Err(e) => Err(format!("LLM title generation failed: {}", e)),
             }
@@ -426,7 +427,7 @@ impl ConsolidationEngine {
                 content
             );
 
-            match reflection_engine.call_ollama(&prompt).await {
+            match reflection_engine.generate(&prompt).await {
                 Ok(response) => Ok(response.trim().to_string()),
                 Err(e) => Err(format!("LLM summary generation failed: {}", e)),
             }
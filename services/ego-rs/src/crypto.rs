@@ -0,0 +1,74 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+
+/// Length in bytes of the random nonce `seal` prefixes onto every
+/// ciphertext; `XChaCha20Poly1305`'s extended nonce is large enough that a
+/// fresh random one per record can't plausibly repeat across a store's
+/// lifetime.
+const NONCE_LEN: usize = 24;
+
+/// Symmetric key for encryption-at-rest, following the cryptoblob approach
+/// Aerogramme/Garage use for sealing records: each record gets its own
+/// random nonce, stored alongside the ciphertext so the key itself never
+/// has to track per-record state.
+#[derive(Clone)]
+pub struct EncryptionKey(XChaCha20Poly1305);
+
+impl EncryptionKey {
+    /// Load a key from 64 hex characters (32 raw bytes), e.g. from
+    /// `config.encryption_key` or the `LJ_ENCRYPTION_KEY` env var.
+    pub fn from_hex(hex: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let bytes = decode_hex(hex)?;
+        if bytes.len() != 32 {
+            return Err(format!(
+                "encryption key must be 32 bytes (64 hex chars), got {}",
+                bytes.len()
+            )
+            .into());
+        }
+        Ok(Self(XChaCha20Poly1305::new_from_slice(&bytes)?))
+    }
+
+    /// Seal `plaintext` under a fresh random nonce and base64-encode the
+    /// `nonce || ciphertext` pair so it's safe to write as one JSONL line.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| format!("encryption failed: {}", e))?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(BASE64.encode(sealed))
+    }
+
+    /// Inverse of `seal`: base64-decode, split the leading nonce back off,
+    /// and decrypt.
+    pub fn open(&self, sealed_b64: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let sealed = BASE64.decode(sealed_b64.trim())?;
+        if sealed.len() < NONCE_LEN {
+            return Err("sealed record too short to contain a nonce".into());
+        }
+        let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = XNonce::from_slice(nonce_bytes);
+        self.0
+            .decrypt(nonce, ciphertext)
+            .map_err(|e| format!("decryption failed: {}", e).into())
+    }
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let s = s.trim();
+    if s.len() % 2 != 0 {
+        return Err("hex key must have an even number of characters".into());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| e.into()))
+        .collect()
+}
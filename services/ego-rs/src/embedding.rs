@@ -0,0 +1,130 @@
+use crate::config::Config;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::time::Duration;
+
+/// Turns free text into a vector, so `MemoryStore` can do semantic (cosine)
+/// search over stored memories instead of modality/recency filters alone.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Ollama's `/api/embeddings` endpoint.
+pub struct OllamaEmbeddingProvider {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(base_url: String, model: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            client,
+            base_url,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let response = self
+            .client
+            .post(format!("{}/api/embeddings", self.base_url))
+            .json(&json!({ "model": self.model, "prompt": text }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama embeddings API error: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let embedding = body["embedding"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Ollama embeddings response missing 'embedding'"))?
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .map(|v| v as f32)
+            .collect();
+
+        Ok(embedding)
+    }
+}
+
+/// OpenAI's `/v1/embeddings` endpoint (`text-embedding-*` models).
+pub struct OpenAiEmbeddingProvider {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            client,
+            base_url,
+            api_key,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let response = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({ "model": self.model, "input": text }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("OpenAI embeddings API error: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let embedding = body["data"][0]["embedding"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("OpenAI embeddings response missing 'data[0].embedding'"))?
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .map(|v| v as f32)
+            .collect();
+
+        Ok(embedding)
+    }
+}
+
+/// Build an `EmbeddingProvider` from `config.embedding_provider`.
+pub fn from_config(config: &Config) -> Box<dyn EmbeddingProvider> {
+    match config.embedding_provider.as_str() {
+        "openai" => Box::new(OpenAiEmbeddingProvider::new(
+            config
+                .api_base
+                .clone()
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            config.api_key.clone().unwrap_or_default(),
+            config.embedding_model.clone(),
+        )),
+        _ => Box::new(OllamaEmbeddingProvider::new(
+            config.ollama_url.clone(),
+            config.embedding_model.clone(),
+        )),
+    }
+}
@@ -1,19 +1,32 @@
 use crate::{
     consolidation::ConsolidationEngine,
+    crypto::EncryptionKey,
+    embedding::EmbeddingProvider,
     memory::{select_relevant_memories, MemoryStore},
+    memory_backend,
     reflection::ReflectionEngine,
     types::{
-        ApiResponse, ConsolidationRequest, ConsolidationResult, EgoThought, Experience, Memory,
-        MemoryQuery,
+        ApiResponse, BatchRequest, BatchResult, ConsolidationRequest, ConsolidationResult,
+        EgoThought, Experience, Memory, MemoryQuery, PollQuery, PollResult, RotateKeyRequest,
+        RotateKeyResult,
     },
 };
+use crate::telemetry::Telemetry;
 use anyhow::Result;
 use chrono::Utc;
+use futures::StreamExt;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::Instrument;
 use uuid::Uuid;
 use warp::reply::json;
 
+/// Default/maximum hold time for `/memories/poll`, in milliseconds.
+const DEFAULT_POLL_TIMEOUT_MS: u64 = 25_000;
+const MAX_POLL_TIMEOUT_MS: u64 = 55_000;
+
 fn generate_fallback_thought(memories: &[&Memory], user_query: Option<&str>) -> EgoThought {
     // Analyze the memories to generate a simple thought
     let mut vision_count = 0;
@@ -97,14 +110,73 @@ fn generate_fallback_thought(memories: &[&Memory], user_query: Option<&str>) ->
     }
 }
 
+/// Shared by `reflect` and `reflect_stream`: turn a finished `EgoThought`
+/// into the `Memory` record it's persisted as. Wrapped in `catch_unwind`
+/// since `serde_json::Number::from_f64` construction runs on caller-supplied
+/// metrics.
+fn thought_to_memory(thought: &EgoThought) -> Result<Memory, ()> {
+    std::panic::catch_unwind(|| Memory {
+        id: thought.id.clone(),
+        timestamp: Utc::now(), // Use current time for simplicity
+        modality: crate::types::Modality::Text,
+        embedding: vec![], // Thoughts don't have embeddings in this context
+        content: thought.thought.clone(),
+        facets: {
+            let mut facets = std::collections::HashMap::new();
+            facets.insert(
+                "self_awareness".to_string(),
+                serde_json::Value::Number(
+                    serde_json::Number::from_f64(thought.metrics.self_awareness as f64)
+                        .unwrap_or(serde_json::Number::from(0)),
+                ),
+            );
+            facets.insert(
+                "memory_consolidation_need".to_string(),
+                serde_json::Value::Number(
+                    serde_json::Number::from_f64(thought.metrics.memory_consolidation_need as f64)
+                        .unwrap_or(serde_json::Number::from(0)),
+                ),
+            );
+            facets.insert(
+                "emotional_stability".to_string(),
+                serde_json::Value::Number(
+                    serde_json::Number::from_f64(thought.metrics.emotional_stability as f64)
+                        .unwrap_or(serde_json::Number::from(0)),
+                ),
+            );
+            facets.insert(
+                "creative_insight".to_string(),
+                serde_json::Value::Number(
+                    serde_json::Number::from_f64(thought.metrics.creative_insight as f64)
+                        .unwrap_or(serde_json::Number::from(0)),
+                ),
+            );
+            facets.insert(
+                "context_hash".to_string(),
+                serde_json::Value::String(thought.context_hash.clone()),
+            );
+            facets
+        },
+        tags: vec!["thought".to_string(), "ego".to_string()],
+        source_ref: None,
+    })
+    .map_err(|_| ())
+}
+
+#[tracing::instrument(skip_all, name = "reflect")]
 pub async fn reflect(
     request: crate::types::ReflectionRequest,
     memory_store: Arc<RwLock<MemoryStore>>,
     reflection_engine: Arc<ReflectionEngine>,
+    embedding_provider: Arc<dyn EmbeddingProvider>,
+    telemetry: Arc<Telemetry>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     // Get existing thoughts from store (release read lock immediately)
     let existing_thoughts: Vec<Memory> = {
-        let store = memory_store.read().await;
+        let store = memory_store
+            .read()
+            .instrument(tracing::info_span!("memory_store.read_lock"))
+            .await;
         store.get_all_memories().into_iter().cloned().collect()
     };
 
@@ -112,10 +184,31 @@ pub async fn reflect(
     let mut all_memories = existing_thoughts;
     all_memories.extend(request.memories.iter().cloned());
 
-    // Select relevant memories
+    // Cache an LLM-assigned poignancy rating on any memory that doesn't have
+    // one yet, so the retrieval-ranking step below has an importance signal.
+    for memory in all_memories.iter_mut() {
+        if !memory.facets.contains_key("memory.poignancy") {
+            if let Ok(rating) = reflection_engine.rate_poignancy(&memory.content).await {
+                memory
+                    .facets
+                    .insert("memory.poignancy".to_string(), serde_json::json!(rating));
+                memory_store.write().await.set_poignancy(&memory.id, rating);
+            }
+        }
+    }
+
+    // Select a broad candidate pool by modality diversity; the reflection
+    // engine itself ranks these by recency/importance/relevance and keeps
+    // only the top-k before building the prompt.
     let all_memories_refs: Vec<&Memory> = all_memories.iter().collect();
     let selected_memories =
-        select_relevant_memories(&all_memories_refs, request.focus_embedding.as_deref(), 5);
+        select_relevant_memories(
+            &all_memories_refs,
+            request.focus_embedding.as_deref(),
+            request.user_query.as_deref(),
+            0.5,
+            50,
+        );
 
     // If no memories selected, use limited memories for fallback (max 5)
     let memories_to_use = if selected_memories.is_empty() {
@@ -139,13 +232,22 @@ pub async fn reflect(
         );
     }
 
-    // Generate reflection
+    // Generate reflection, allowing the engine to call back into the
+    // memory store for extra context before settling on a final thought.
+    let reflect_started = Instant::now();
     let thought = match reflection_engine
-        .reflect_on_memories(memories_to_use, request.user_query.as_deref())
+        .reflect_on_memories_with_tools(
+            memories_to_use,
+            request.user_query.as_deref(),
+            request.focus_embedding.as_deref(),
+            &memory_store,
+        )
+        .instrument(tracing::info_span!("ollama.reflect_on_memories_with_tools"))
         .await
     {
         Ok(thought) => {
             tracing::info!("Successfully generated thought via Ollama");
+            telemetry.reflections_served.add(1, &[]);
             thought
         }
         Err(e) => {
@@ -158,63 +260,20 @@ pub async fn reflect(
                 "Generating fallback thought with {} memories",
                 memories_to_use.len()
             );
+            telemetry.fallback_thoughts.add(1, &[]);
             generate_fallback_thought(memories_to_use, request.user_query.as_deref())
         }
     };
+    telemetry
+        .reflect_latency_ms
+        .record(reflect_started.elapsed().as_secs_f64() * 1000.0, &[]);
 
     // Convert EgoThought to Memory and save to store
     tracing::info!("Converting thought to memory and saving...");
 
-    let memory = match std::panic::catch_unwind(|| {
-        Memory {
-            id: thought.id.clone(),
-            timestamp: Utc::now(), // Use current time for simplicity
-            modality: crate::types::Modality::Text,
-            embedding: vec![], // Thoughts don't have embeddings in this context
-            content: thought.thought.clone(),
-            facets: {
-                let mut facets = std::collections::HashMap::new();
-                facets.insert(
-                    "self_awareness".to_string(),
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(thought.metrics.self_awareness as f64)
-                            .unwrap_or(serde_json::Number::from(0)),
-                    ),
-                );
-                facets.insert(
-                    "memory_consolidation_need".to_string(),
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(
-                            thought.metrics.memory_consolidation_need as f64,
-                        )
-                        .unwrap_or(serde_json::Number::from(0)),
-                    ),
-                );
-                facets.insert(
-                    "emotional_stability".to_string(),
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(thought.metrics.emotional_stability as f64)
-                            .unwrap_or(serde_json::Number::from(0)),
-                    ),
-                );
-                facets.insert(
-                    "creative_insight".to_string(),
-                    serde_json::Value::Number(
-                        serde_json::Number::from_f64(thought.metrics.creative_insight as f64)
-                            .unwrap_or(serde_json::Number::from(0)),
-                    ),
-                );
-                facets.insert(
-                    "context_hash".to_string(),
-                    serde_json::Value::String(thought.context_hash.clone()),
-                );
-                facets
-            },
-            tags: vec!["thought".to_string(), "ego".to_string()],
-        }
-    }) {
+    let memory = match thought_to_memory(&thought) {
         Ok(memory) => memory,
-        Err(_) => {
+        Err(()) => {
             tracing::error!("Panic during memory conversion");
             return Ok(json(&ApiResponse::<EgoThought>::error(
                 "Memory conversion failed".to_string(),
@@ -224,14 +283,23 @@ pub async fn reflect(
 
     // Save the thought to the store
     tracing::info!("Acquiring write lock for memory store...");
-    let mut store = memory_store.write().await;
+    let mut store = memory_store
+        .write()
+        .instrument(tracing::info_span!("memory_store.write_lock"))
+        .await;
     tracing::info!("Write lock acquired, attempting to save memory...");
 
-    // Try to add memory first
-    store.add_memory(memory.clone());
-    tracing::info!("Memory added to store, attempting to save to file...");
+    // Best-effort: embed the thought's content before persisting, so it's
+    // reachable via semantic search immediately instead of needing a later
+    // resave. A failure here shouldn't block saving the thought itself.
+    let mut memory = memory;
+    if let Ok(thought_embedding) = embedding_provider.embed(&memory.content).await {
+        memory.embedding = thought_embedding;
+    }
+
+    tracing::info!("Appending memory to store...");
 
-    if let Err(e) = store.save_all_memories() {
+    if let Err(e) = store.add_memory_and_save(memory) {
         tracing::error!("Failed to save thought to file: {}", e);
         // Continue even if save fails - don't return error to user
     } else {
@@ -244,12 +312,135 @@ pub async fn reflect(
     Ok(json(&ApiResponse::success(thought)))
 }
 
+/// Streaming counterpart to `reflect`: forwards the model's response as SSE
+/// `token` events while they arrive, then emits a single `thought` event
+/// with the same persisted `EgoThought` the non-streaming endpoint would
+/// have returned (or an `error` event if generation/parsing failed).
+pub async fn reflect_stream(
+    request: crate::types::ReflectionRequest,
+    memory_store: Arc<RwLock<MemoryStore>>,
+    reflection_engine: Arc<ReflectionEngine>,
+    embedding_provider: Arc<dyn EmbeddingProvider>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let existing_thoughts: Vec<Memory> = {
+        let store = memory_store.read().await;
+        store.get_all_memories().into_iter().cloned().collect()
+    };
+
+    let mut all_memories = existing_thoughts;
+    all_memories.extend(request.memories.iter().cloned());
+
+    let all_memories_refs: Vec<&Memory> = all_memories.iter().collect();
+    let selected_memories =
+        select_relevant_memories(
+            &all_memories_refs,
+            request.focus_embedding.as_deref(),
+            request.user_query.as_deref(),
+            0.5,
+            50,
+        );
+    let memories_to_use: &[&Memory] = if selected_memories.is_empty() {
+        &all_memories_refs[..all_memories_refs.len().min(5)]
+    } else {
+        &selected_memories
+    };
+
+    let event_stream: std::pin::Pin<
+        Box<dyn futures::Stream<Item = Result<warp::sse::Event, std::convert::Infallible>> + Send>,
+    > = match reflection_engine
+        .reflect_on_memories_stream(
+            memories_to_use,
+            request.user_query.as_deref(),
+            request.focus_embedding.as_deref(),
+        )
+        .await
+    {
+        Ok(streaming) => {
+            let accumulated = Arc::new(std::sync::Mutex::new(String::new()));
+            let accumulated_for_tokens = accumulated.clone();
+
+            let token_events = streaming.tokens.map(move |chunk| match chunk {
+                Ok(text) => {
+                    accumulated_for_tokens.lock().unwrap().push_str(&text);
+                    Ok(warp::sse::Event::default().event("token").data(text))
+                }
+                Err(e) => Ok(warp::sse::Event::default().event("error").data(e.to_string())),
+            });
+
+            let context_hash = streaming.context_hash;
+            let model = streaming.model;
+
+            let final_event = futures::stream::once(async move {
+                let full_text = accumulated.lock().unwrap().clone();
+
+                let thought = match reflection_engine.build_thought(&full_text, context_hash, model)
+                {
+                    Ok(thought) => thought,
+                    Err(e) => {
+                        return Ok(warp::sse::Event::default()
+                            .event("error")
+                            .data(format!("Failed to parse reflection: {}", e)));
+                    }
+                };
+
+                let mut memory = match thought_to_memory(&thought) {
+                    Ok(memory) => memory,
+                    Err(()) => {
+                        return Ok(warp::sse::Event::default()
+                            .event("error")
+                            .data("Memory conversion failed".to_string()));
+                    }
+                };
+
+                if let Ok(thought_embedding) = embedding_provider.embed(&memory.content).await {
+                    memory.embedding = thought_embedding;
+                }
+
+                let mut store = memory_store.write().await;
+                if let Err(e) = store.add_memory_and_save(memory) {
+                    tracing::error!("Failed to save streamed thought to file: {}", e);
+                }
+
+                let payload = serde_json::to_string(&ApiResponse::success(thought))
+                    .unwrap_or_else(|_| "{}".to_string());
+                Ok(warp::sse::Event::default().event("thought").data(payload))
+            });
+
+            Box::pin(token_events.chain(final_event))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to start streaming reflection: {}", e);
+            Box::pin(futures::stream::once(async move {
+                Ok(warp::sse::Event::default().event("error").data(e.to_string()))
+            }))
+        }
+    };
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(event_stream)))
+}
+
 pub async fn get_memories(
     query: MemoryQuery,
     memory_store: Arc<RwLock<MemoryStore>>,
+    embedding_provider: Arc<dyn EmbeddingProvider>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let store = memory_store.read().await;
 
+    // Semantic search: rank by embedding similarity to `q` instead of the
+    // usual modality/recency filters.
+    if let Some(q) = &query.q {
+        let memories = match embedding_provider.embed(q).await {
+            Ok(query_embedding) => {
+                store.nearest(&query_embedding, query.limit.unwrap_or(10))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to embed semantic query: {}", e);
+                Vec::new()
+            }
+        };
+        return Ok(json(&ApiResponse::success(memories)));
+    }
+
     let mut memories = if let Some(modality) = query.modality {
         store.get_memories_by_modality(&modality)
     } else {
@@ -273,6 +464,113 @@ pub async fn get_memories(
     Ok(json(&ApiResponse::success(memories)))
 }
 
+/// Batch read/insert endpoint: mirrors Garage's K2V batch interface by
+/// running every `MemoryQuery` read and every `Memory` insert under a
+/// single `MemoryStore` lock acquisition — each insert is appended via
+/// `add_memory_and_save` as it's applied, instead of the per-record
+/// lock/save pair that syncing dozens of memories through
+/// `get_memories`/`reflect` one at a time would incur.
+pub async fn batch_memories(
+    request: BatchRequest,
+    memory_store: Arc<RwLock<MemoryStore>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut store = memory_store.write().await;
+
+    for memory in request.inserts.iter().cloned() {
+        if let Err(e) = store.add_memory_and_save(memory) {
+            tracing::error!("Failed to persist batch-inserted memory: {}", e);
+        }
+    }
+    let inserted = request.inserts.len();
+
+    let reads: Vec<Vec<Memory>> = request
+        .reads
+        .iter()
+        .map(|query| {
+            let mut memories = if let Some(modality) = &query.modality {
+                store.get_memories_by_modality(modality)
+            } else {
+                store.get_all_memories()
+            };
+
+            if let Some(since) = query.since {
+                memories.retain(|m| m.timestamp >= since);
+            }
+
+            memories.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+            if let Some(limit) = query.limit {
+                memories.truncate(limit);
+            }
+
+            memories.into_iter().cloned().collect()
+        })
+        .collect();
+
+    Ok(json(&ApiResponse::success(BatchResult { reads, inserted })))
+}
+
+/// Long-poll endpoint: blocks until a memory or experience created after
+/// `query.since` appears, or `query.timeout` (capped at
+/// `MAX_POLL_TIMEOUT_MS`) elapses, then returns the delta plus a `cursor`
+/// to pass as `since` on the next call. Lets a UI watch for new
+/// reflections/consolidations without busy-polling `get_memories` in a loop.
+pub async fn poll_memories(
+    query: PollQuery,
+    memory_store: Arc<RwLock<MemoryStore>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let timeout_ms = query
+        .timeout
+        .unwrap_or(DEFAULT_POLL_TIMEOUT_MS)
+        .min(MAX_POLL_TIMEOUT_MS);
+    let deadline = Instant::now() + std::time::Duration::from_millis(timeout_ms);
+
+    let (changed, last_mutation_ms) = memory_store.read().await.subscribe_changes();
+
+    loop {
+        // Register interest before checking the condition: if a mutation
+        // lands between the check below and the `.await`, `notify_waiters`
+        // still wakes this registration, so no update can be missed.
+        let notified = changed.notified();
+
+        let (memories, experiences) = {
+            let store = memory_store.read().await;
+            (
+                store
+                    .memories_since(query.since)
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                store
+                    .experiences_since(query.since)
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        if !memories.is_empty() || !experiences.is_empty() {
+            let cursor = last_mutation_ms.load(Ordering::SeqCst).max(query.since);
+            return Ok(json(&ApiResponse::success(PollResult {
+                memories,
+                experiences,
+                cursor,
+            })));
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(json(&ApiResponse::success(PollResult {
+                memories,
+                experiences,
+                cursor: query.since,
+            })));
+        }
+
+        let _ = tokio::time::timeout(deadline - now, notified).await;
+    }
+}
+
 pub async fn clear_data(
     memory_store: Arc<RwLock<MemoryStore>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
@@ -280,12 +578,9 @@ pub async fn clear_data(
 
     let mut store = memory_store.write().await;
 
-    // Clear all memories from the store
-    store.clear_all_memories();
-
-    // Save the empty store to file
-    if let Err(e) = store.save_all_memories() {
-        tracing::error!("Failed to save empty memory store: {}", e);
+    // Clear all memories from the store and the backend
+    if let Err(e) = store.clear_all_memories() {
+        tracing::error!("Failed to clear memory backend: {}", e);
         return Ok(json(&ApiResponse::<()>::error(
             "Failed to clear data".to_string(),
         )));
@@ -295,6 +590,64 @@ pub async fn clear_data(
     Ok(json(&ApiResponse::success(())))
 }
 
+/// Re-seal the STM JSONL file from `request.old_key` to `request.new_key`
+/// via `memory_backend::rotate_jsonl_key`. Only meaningful when
+/// `persistence_mode = "encrypted"`.
+///
+/// This rewrites the file on disk only — the running server's STM backend
+/// was built with `config.encryption_key` at startup and keeps decrypting
+/// with the old key for the rest of this process's lifetime. After a
+/// successful rotation, restart the service with `encryption_key` (or
+/// `LJ_ENCRYPTION_KEY`) set to `request.new_key` so the live backend picks
+/// up the rotated file.
+pub async fn rotate_stm_key(
+    request: RotateKeyRequest,
+    jsonl_path: String,
+    persistence_mode: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if persistence_mode != "encrypted" {
+        return Ok(json(&ApiResponse::<RotateKeyResult>::error(
+            "persistence_mode is not \"encrypted\"; there is no key to rotate".to_string(),
+        )));
+    }
+
+    let old_key = match EncryptionKey::from_hex(&request.old_key) {
+        Ok(key) => key,
+        Err(e) => {
+            return Ok(json(&ApiResponse::<RotateKeyResult>::error(format!(
+                "Invalid old_key: {}",
+                e
+            ))))
+        }
+    };
+    let new_key = match EncryptionKey::from_hex(&request.new_key) {
+        Ok(key) => key,
+        Err(e) => {
+            return Ok(json(&ApiResponse::<RotateKeyResult>::error(format!(
+                "Invalid new_key: {}",
+                e
+            ))))
+        }
+    };
+
+    match memory_backend::rotate_jsonl_key(&jsonl_path, &old_key, &new_key) {
+        Ok(rotated) => {
+            tracing::warn!(
+                "Re-sealed {} STM record(s) at {} under a new key; restart ego-rs with \
+                 encryption_key/LJ_ENCRYPTION_KEY set to the new key so the running backend \
+                 decrypts them",
+                rotated,
+                jsonl_path
+            );
+            Ok(json(&ApiResponse::success(RotateKeyResult { rotated })))
+        }
+        Err(e) => Ok(json(&ApiResponse::<RotateKeyResult>::error(format!(
+            "Failed to rotate key: {}",
+            e
+        )))),
+    }
+}
+
 pub async fn status(
     reflection_engine: Arc<ReflectionEngine>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
@@ -327,10 +680,13 @@ pub async fn status(
     Ok(json(&status))
 }
 
+#[tracing::instrument(skip_all, name = "consolidate_stm_to_ltm")]
 pub async fn consolidate_stm_to_ltm(
     memory_store: Arc<RwLock<MemoryStore>>,
     reflection_engine: Arc<ReflectionEngine>,
+    embedding_provider: Arc<dyn EmbeddingProvider>,
     request: ConsolidationRequest,
+    telemetry: Arc<Telemetry>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::info!("Starting STM to LTM consolidation...");
 
@@ -338,7 +694,10 @@ pub async fn consolidate_stm_to_ltm(
 
     // Get all memories from STM
     let memories: Vec<Memory> = {
-        let store = memory_store.read().await;
+        let store = memory_store
+            .read()
+            .instrument(tracing::info_span!("memory_store.read_lock"))
+            .await;
         store.get_all_memories().into_iter().cloned().collect()
     };
 
@@ -354,8 +713,10 @@ pub async fn consolidate_stm_to_ltm(
     }
 
     // Perform consolidation
+    let consolidate_started = Instant::now();
     let result = match consolidation_engine
         .consolidate_thoughts(&memories, &request)
+        .instrument(tracing::info_span!("ollama.consolidate_thoughts"))
         .await
     {
         Ok(result) => result,
@@ -367,6 +728,9 @@ pub async fn consolidate_stm_to_ltm(
             ))));
         }
     };
+    telemetry
+        .consolidate_latency_ms
+        .record(consolidate_started.elapsed().as_secs_f64() * 1000.0, &[]);
 
     if result.experiences_created > 0 {
         // Create experiences and add them to LTM
@@ -386,7 +750,21 @@ pub async fn consolidate_stm_to_ltm(
                 .await
             {
                 Ok(experience) => {
-                    let mut store = memory_store.write().await;
+                    // Best-effort: embed the experience's summary so it's
+                    // reachable via semantic search, same as `reflect` does
+                    // for a thought's content. A failure here shouldn't
+                    // block consolidation itself.
+                    let mut experience = experience;
+                    if let Ok(summary_embedding) =
+                        embedding_provider.embed(&experience.summary).await
+                    {
+                        experience.embedding = summary_embedding;
+                    }
+
+                    let mut store = memory_store
+                        .write()
+                        .instrument(tracing::info_span!("memory_store.write_lock"))
+                        .await;
                     store.add_experience(experience);
                     experiences_added += 1;
                 }
@@ -404,6 +782,11 @@ pub async fn consolidate_stm_to_ltm(
             }
         }
 
+        telemetry.consolidations_run.add(1, &[]);
+        telemetry
+            .experiences_created
+            .add(experiences_added as u64, &[]);
+
         tracing::info!(
             "Consolidation completed: {} experiences created, {} thoughts consolidated",
             experiences_added,
@@ -417,20 +800,33 @@ pub async fn consolidate_stm_to_ltm(
 pub async fn get_ltm_experiences(
     memory_store: Arc<RwLock<MemoryStore>>,
     query: MemoryQuery,
+    embedding_provider: Arc<dyn EmbeddingProvider>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::info!("Retrieving LTM experiences...");
 
     let store = memory_store.read().await;
     let mut experiences = store.get_experiences();
 
+    // Semantic search: rank by embedding similarity to `q` over experience
+    // summaries instead of plain recency.
+    if let Some(q) = &query.q {
+        if let Ok(query_embedding) = embedding_provider.embed(q).await {
+            experiences.sort_by(|a, b| {
+                let score_a = crate::memory::cosine_similarity(&a.embedding, &query_embedding);
+                let score_b = crate::memory::cosine_similarity(&b.embedding, &query_embedding);
+                score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+    } else {
+        // Sort by creation time (newest first)
+        experiences.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    }
+
     // Apply limit if specified
     if let Some(limit) = query.limit {
         experiences.truncate(limit);
     }
 
-    // Sort by creation time (newest first)
-    experiences.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-
     tracing::info!("Retrieved {} experiences from LTM", experiences.len());
     Ok(json(&ApiResponse::success(experiences)))
 }
@@ -457,6 +853,67 @@ pub async fn get_ltm_experience(
     }
 }
 
+/// Remove a single memory through the configured `MemoryBackend` (a
+/// transactional delete on `SqliteBackend`, a full rewrite of `stm.jsonl` on
+/// the default `JsonlBackend`). Also rewrites `ltm.jsonl` since any
+/// experience that consolidated this memory has its provenance updated
+/// rather than left dangling.
+pub async fn redact_memory(
+    id: String,
+    memory_store: Arc<RwLock<MemoryStore>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut store = memory_store.write().await;
+
+    let existed = match store.redact_memory(&id) {
+        Ok(existed) => existed,
+        Err(e) => {
+            tracing::error!("Failed to redact memory {}: {}", id, e);
+            return Ok(json(&ApiResponse::<()>::error(
+                "Failed to persist redaction".to_string(),
+            )));
+        }
+    };
+
+    if !existed {
+        return Ok(json(&ApiResponse::<()>::error(format!(
+            "Memory not found: {}",
+            id
+        ))));
+    }
+
+    if let Err(e) = store.save_ltm_to_jsonl() {
+        tracing::error!("Failed to rewrite ltm.jsonl after redaction: {}", e);
+    }
+
+    tracing::info!("Redacted memory: {}", id);
+    Ok(json(&ApiResponse::success(())))
+}
+
+/// Remove a single LTM experience and rewrite `ltm.jsonl`.
+pub async fn redact_experience(
+    id: String,
+    memory_store: Arc<RwLock<MemoryStore>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut store = memory_store.write().await;
+
+    if !store.redact_experience(&id) {
+        return Ok(json(&ApiResponse::<()>::error(format!(
+            "Experience not found: {}",
+            id
+        ))));
+    }
+
+    if let Err(e) = store.save_ltm_to_jsonl() {
+        tracing::error!("Failed to rewrite ltm.jsonl after redaction: {}", e);
+        return Ok(json(&ApiResponse::<()>::error(
+            "Failed to persist redaction".to_string(),
+        )));
+    }
+
+    tracing::info!("Redacted experience: {}", id);
+    Ok(json(&ApiResponse::success(())))
+}
+
 pub async fn clear_ltm_data(
     memory_store: Arc<RwLock<MemoryStore>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
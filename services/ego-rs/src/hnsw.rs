@@ -0,0 +1,343 @@
+//! In-memory HNSW (Hierarchical Navigable Small World) index over memory
+//! embeddings, replacing the O(n·d) linear cosine scan `MemoryStore::nearest`
+//! used to do. Standard construction: each inserted vector draws a random
+//! max layer `L` from an exponential distribution
+//! (`floor(-ln(uniform) * level_mult)`), is linked into a multi-layer
+//! proximity graph, and `search` greedily descends from the entry point's
+//! top layer to layer 0, widening the candidate set only at the bottom.
+
+use crate::memory::cosine_similarity;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Max neighbors kept per node at layers above 0.
+const DEFAULT_M: usize = 16;
+/// Max neighbors kept per node at layer 0 (conventionally `2*M`).
+const DEFAULT_M0: usize = 32;
+/// Candidate list size explored while inserting; higher is more accurate,
+/// slower to build.
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+/// Candidate list size explored while searching; higher is more accurate,
+/// slower to query.
+const DEFAULT_EF_SEARCH: usize = 50;
+
+struct Node {
+    embedding: Vec<f32>,
+    /// `neighbors[level]` is this node's neighbor ids at that layer.
+    neighbors: Vec<Vec<String>>,
+}
+
+/// A candidate during greedy search, ordered by similarity (max-heap via
+/// `BinaryHeap`, reversed for the min-heap used to prune the "far" side).
+#[derive(Clone)]
+struct Candidate {
+    similarity: f32,
+    id: String,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.similarity == other.similarity
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .partial_cmp(&other.similarity)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Min-heap wrapper (reverses `Candidate`'s ordering) used to track the
+/// `ef`-nearest-so-far set and evict the worst when it overflows.
+#[derive(Clone)]
+struct MinCandidate(Candidate);
+impl PartialEq for MinCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for MinCandidate {}
+impl PartialOrd for MinCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for MinCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+pub struct HnswIndex {
+    nodes: HashMap<String, Node>,
+    entry_point: Option<String>,
+    m: usize,
+    m0: usize,
+    ef_construction: usize,
+    /// `1 / ln(m)`, the standard level-multiplier so the expected number of
+    /// layers stays logarithmic in the node count.
+    level_mult: f64,
+}
+
+impl HnswIndex {
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            entry_point: None,
+            m: DEFAULT_M,
+            m0: DEFAULT_M0,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            level_mult: 1.0 / (DEFAULT_M as f64).ln(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Remove `id` from the index, if present, and from every remaining
+    /// node's neighbor lists.
+    pub fn remove(&mut self, id: &str) {
+        if self.nodes.remove(id).is_none() {
+            return;
+        }
+        for node in self.nodes.values_mut() {
+            for layer in node.neighbors.iter_mut() {
+                layer.retain(|n| n != id);
+            }
+        }
+        if self.entry_point.as_deref() == Some(id) {
+            self.entry_point = self
+                .nodes
+                .iter()
+                .max_by_key(|(_, n)| n.neighbors.len())
+                .map(|(id, _)| id.clone());
+        }
+    }
+
+    fn random_level(&self) -> usize {
+        let uniform: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        (-uniform.ln() * self.level_mult).floor() as usize
+    }
+
+    /// Insert or replace the vector stored for `id`. Re-inserting an id that
+    /// already exists removes the stale node first so it doesn't linger in
+    /// other nodes' neighbor lists with an outdated embedding.
+    pub fn insert(&mut self, id: String, embedding: Vec<f32>) {
+        self.remove(&id);
+
+        let level = self.random_level();
+        let new_node = Node {
+            embedding: embedding.clone(),
+            neighbors: vec![Vec::new(); level + 1],
+        };
+
+        let Some(entry_id) = self.entry_point.clone() else {
+            self.nodes.insert(id.clone(), new_node);
+            self.entry_point = Some(id);
+            return;
+        };
+
+        let entry_level = self.nodes[&entry_id].neighbors.len() - 1;
+        let mut current = entry_id;
+
+        // Greedily descend to the new node's top layer, single best hop per
+        // layer — we don't need breadth until we're at a layer we'll link in.
+        for layer in (level + 1..=entry_level).rev() {
+            current = self.greedy_closest(&current, &embedding, layer);
+        }
+
+        self.nodes.insert(id.clone(), new_node);
+
+        for layer in (0..=level.min(entry_level)).rev() {
+            let candidates = self.search_layer(&embedding, &current, self.ef_construction, layer);
+            let max_neighbors = if layer == 0 { self.m0 } else { self.m };
+            let selected = Self::select_neighbors(&candidates, max_neighbors);
+
+            for neighbor_id in &selected {
+                self.link(&id, neighbor_id, layer);
+                self.link(neighbor_id, &id, layer);
+                self.trim_neighbors(neighbor_id, layer);
+            }
+
+            if let Some(best) = selected.first() {
+                current = best.clone();
+            }
+        }
+
+        if level > entry_level {
+            self.entry_point = Some(id);
+        }
+    }
+
+    fn link(&mut self, from: &str, to: &str, layer: usize) {
+        if let Some(node) = self.nodes.get_mut(from) {
+            if layer < node.neighbors.len() && !node.neighbors[layer].contains(&to.to_string()) {
+                node.neighbors[layer].push(to.to_string());
+            }
+        }
+    }
+
+    /// Keep only `from`'s closest neighbors at `layer`, evicting the
+    /// farthest once the degree bound is exceeded.
+    fn trim_neighbors(&mut self, from: &str, layer: usize) {
+        let max_neighbors = if layer == 0 { self.m0 } else { self.m };
+        let Some(node) = self.nodes.get(from) else {
+            return;
+        };
+        if node.neighbors[layer].len() <= max_neighbors {
+            return;
+        }
+        let embedding = node.embedding.clone();
+        let candidates: Vec<Candidate> = node.neighbors[layer]
+            .iter()
+            .filter_map(|id| {
+                self.nodes.get(id).map(|n| Candidate {
+                    similarity: cosine_similarity(&embedding, &n.embedding),
+                    id: id.clone(),
+                })
+            })
+            .collect();
+        let kept = Self::select_neighbors(&candidates, max_neighbors);
+        if let Some(node) = self.nodes.get_mut(from) {
+            node.neighbors[layer] = kept;
+        }
+    }
+
+    fn greedy_closest(&self, from: &str, query: &[f32], layer: usize) -> String {
+        let mut current = from.to_string();
+        let mut current_similarity = self
+            .nodes
+            .get(&current)
+            .map(|n| cosine_similarity(query, &n.embedding))
+            .unwrap_or(f32::NEG_INFINITY);
+
+        loop {
+            let mut improved = false;
+            if let Some(node) = self.nodes.get(&current) {
+                if let Some(layer_neighbors) = node.neighbors.get(layer) {
+                    for neighbor_id in layer_neighbors {
+                        if let Some(neighbor) = self.nodes.get(neighbor_id) {
+                            let sim = cosine_similarity(query, &neighbor.embedding);
+                            if sim > current_similarity {
+                                current = neighbor_id.clone();
+                                current_similarity = sim;
+                                improved = true;
+                            }
+                        }
+                    }
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Best-first search of `layer`, starting from `entry`, expanding up to
+    /// `ef` candidates. Returns candidates sorted by descending similarity.
+    fn search_layer(&self, query: &[f32], entry: &str, ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: HashSet<String> = HashSet::new();
+        visited.insert(entry.to_string());
+
+        let Some(entry_node) = self.nodes.get(entry) else {
+            return Vec::new();
+        };
+        let entry_similarity = cosine_similarity(query, &entry_node.embedding);
+        let entry_candidate = Candidate {
+            similarity: entry_similarity,
+            id: entry.to_string(),
+        };
+
+        let mut frontier: BinaryHeap<Candidate> = BinaryHeap::new();
+        frontier.push(entry_candidate.clone());
+        let mut found: BinaryHeap<MinCandidate> = BinaryHeap::new();
+        found.push(MinCandidate(entry_candidate));
+
+        while let Some(closest) = frontier.pop() {
+            let worst_found = found.peek().map(|c| c.0.similarity).unwrap_or(f32::MIN);
+            if closest.similarity < worst_found && found.len() >= ef {
+                break;
+            }
+
+            if let Some(node) = self.nodes.get(&closest.id) {
+                if let Some(layer_neighbors) = node.neighbors.get(layer) {
+                    for neighbor_id in layer_neighbors.clone() {
+                        if !visited.insert(neighbor_id.clone()) {
+                            continue;
+                        }
+                        if let Some(neighbor) = self.nodes.get(&neighbor_id) {
+                            let sim = cosine_similarity(query, &neighbor.embedding);
+                            let worst_found =
+                                found.peek().map(|c| c.0.similarity).unwrap_or(f32::MIN);
+                            if found.len() < ef || sim > worst_found {
+                                let candidate = Candidate {
+                                    similarity: sim,
+                                    id: neighbor_id,
+                                };
+                                frontier.push(candidate.clone());
+                                found.push(MinCandidate(candidate));
+                                if found.len() > ef {
+                                    found.pop();
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<Candidate> = found.into_iter().map(|c| c.0).collect();
+        result.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(Ordering::Equal));
+        result
+    }
+
+    /// Simple neighbor-selection heuristic: keep the `max` closest
+    /// candidates. (The HNSW paper's diversity heuristic is a further
+    /// refinement; plain closest-M is the common baseline implementation.)
+    fn select_neighbors(candidates: &[Candidate], max: usize) -> Vec<String> {
+        let mut sorted: Vec<&Candidate> = candidates.iter().collect();
+        sorted.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(Ordering::Equal));
+        sorted.into_iter().take(max).map(|c| c.id.clone()).collect()
+    }
+
+    /// Approximate k-NN search for `query`, returning ids ordered by
+    /// descending similarity.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        let Some(entry_id) = self.entry_point.clone() else {
+            return Vec::new();
+        };
+
+        let entry_level = self.nodes[&entry_id].neighbors.len() - 1;
+        let mut current = entry_id;
+        for layer in (1..=entry_level).rev() {
+            current = self.greedy_closest(&current, query, layer);
+        }
+
+        let ef = DEFAULT_EF_SEARCH.max(k);
+        let candidates = self.search_layer(query, &current, ef, 0);
+        candidates
+            .into_iter()
+            .take(k)
+            .map(|c| (c.id, c.similarity))
+            .collect()
+    }
+}
+
+impl Default for HnswIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,17 @@
+#[cfg(feature = "async-memory")]
+pub mod async_memory;
+pub mod backend;
 pub mod config;
+pub mod crypto;
+pub mod embedding;
 pub mod handlers;
+pub mod hnsw;
 pub mod memory;
+pub mod memory_backend;
+pub mod oplog;
 pub mod reflection;
+pub mod telemetry;
+pub mod tools;
 pub mod types;
 
 pub use types::*;
@@ -1,25 +1,46 @@
 use ego_rs::{
-    config::Config, handlers, memory::MemoryStore, reflection::ReflectionEngine,
-    ConsolidationRequest, MemoryQuery,
+    config::Config, embedding, embedding::EmbeddingProvider, handlers, memory::MemoryStore,
+    memory_backend, reflection::ReflectionEngine, ConsolidationRequest, MemoryQuery,
 };
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{info, Level};
+use tracing::info;
 use warp::Filter;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
+    let config = Config::load();
+
+    // Initialize tracing: plain logs, plus an OTLP trace exporter layer when
+    // config.otel_exporter_endpoint is set.
+    ego_rs::telemetry::init_subscriber(&config);
 
-    let config = Config::default();
     info!("Starting Ego service on port {}", config.port);
 
-    // Initialize memory store and load existing thoughts data
-    let mut memory_store = MemoryStore::new_with_path("data/stm.jsonl".to_string());
+    // Initialize memory store (backend selected by config.memory_backend) and
+    // load existing thoughts data
+    let memory_backend = memory_backend::from_config(&config, "data/stm.jsonl".to_string());
+    let mut memory_store = MemoryStore::new_with_backend(memory_backend);
 
-    // Load existing thoughts from ego-rs STM file
-    if let Err(e) = memory_store.load_stm_from_jsonl() {
+    // LTM (`ltm.jsonl`) isn't behind a `MemoryBackend`, so its encryption is
+    // threaded into `MemoryStore` directly rather than selected via
+    // `memory_backend::from_config`.
+    if config.persistence_mode == "encrypted" {
+        match config.encryption_key.as_deref().map(|hex| {
+            ego_rs::crypto::EncryptionKey::from_hex(hex).map_err(|e| e.to_string())
+        }) {
+            Some(Ok(key)) => memory_store.set_ltm_encryption_key(Some(key)),
+            Some(Err(e)) => {
+                tracing::error!("Invalid encryption_key: {}; LTM will stay plaintext", e)
+            }
+            None => tracing::error!(
+                "persistence_mode=encrypted but no encryption_key set; LTM will stay plaintext"
+            ),
+        }
+    }
+
+    // Load existing thoughts from the configured backend
+    if let Err(e) = memory_store.load_memories() {
         tracing::info!("No existing thoughts data found, starting fresh: {}", e);
     }
 
@@ -29,25 +50,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     let memory_store = Arc::new(RwLock::new(memory_store));
 
-    // Start periodic memory save task
-    let memory_store_save = memory_store.clone();
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
-        loop {
-            interval.tick().await;
-            if let Err(e) = memory_store_save.read().await.save_all_memories() {
-                tracing::error!("Failed to save thoughts periodically: {}", e);
-            } else {
-                tracing::debug!("Periodically saved all thoughts to file");
-            }
-        }
-    });
+    // No periodic full-store resave here: every mutation path (reflect,
+    // reflect_stream, batch_memories inserts, redact/clear) now persists
+    // itself immediately via `add_memory_and_save` or an equivalent direct
+    // backend call, so a background `save_all_memories` would just be a
+    // redundant full rewrite - an O(n) replay of `update()` calls (and, for
+    // `OpLogBackend`, N new log entries) every 30s for no new data. The one
+    // exception is the poignancy rating `reflect` caches via `set_poignancy`:
+    // it lives in memory only and is not written back to the backend.
+
+    // Initialize reflection engine (backend selected by config.provider)
+    let reflection_engine = Arc::new(ReflectionEngine::from_config(&config).await);
+
+    // Initialize embedding provider (selected by config.embedding_provider)
+    let embedding_provider: Arc<dyn EmbeddingProvider> = Arc::from(embedding::from_config(&config));
 
-    // Initialize reflection engine
-    let reflection_engine = Arc::new(ReflectionEngine::new(
-        config.ollama_url.clone(),
-        config.model.clone(),
-    ));
+    // Initialize telemetry (OTLP export is a no-op until config.otel_exporter_endpoint
+    // is set) and start the periodic gauge sampler.
+    let telemetry = Arc::new(ego_rs::telemetry::Telemetry::init(&config));
+    telemetry.spawn_gauge_reporter(memory_store.clone(), reflection_engine.clone());
 
     // Health check endpoint
     let health = warp::path("health").and(warp::get()).map(|| {
@@ -72,18 +93,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .and(warp::body::json())
             .and(with_memory_store(memory_store.clone()))
             .and(with_reflection_engine(reflection_engine.clone()))
+            .and(with_embedding_provider(embedding_provider.clone()))
+            .and(with_telemetry(telemetry.clone()))
             .and_then(handlers::reflect),
     );
 
+    // Same reflection, but forwarding tokens over SSE as they're generated.
+    let reflect_stream = warp::path("api").and(warp::path("ego")).and(
+        warp::path("reflect")
+            .and(warp::path("stream"))
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_memory_store(memory_store.clone()))
+            .and(with_reflection_engine(reflection_engine.clone()))
+            .and(with_embedding_provider(embedding_provider.clone()))
+            .and_then(handlers::reflect_stream),
+    );
+
     // Memory query endpoints
     let memories = warp::path("api").and(warp::path("ego")).and(
         warp::path("memories")
             .and(warp::get())
             .and(warp::query())
             .and(with_memory_store(memory_store.clone()))
+            .and(with_embedding_provider(embedding_provider.clone()))
             .and_then(handlers::get_memories),
     );
 
+    // Long-poll watch endpoint: blocks until a memory/experience past
+    // `since` appears, or `timeout` elapses. Listed ahead of `memories` so
+    // its more specific path wins.
+    let memories_poll = warp::path("api").and(warp::path("ego")).and(
+        warp::path("memories")
+            .and(warp::path("poll"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query())
+            .and(with_memory_store(memory_store.clone()))
+            .and_then(handlers::poll_memories),
+    );
+
+    // Batch read/insert endpoint: many MemoryQuery reads and Memory inserts
+    // applied under a single MemoryStore lock. Each insert is appended to the
+    // backend as it is applied; there is no single save at the end. Listed
+    // ahead of `memories` so its more specific path wins.
+    let memories_batch = warp::path("api").and(warp::path("ego")).and(
+        warp::path("memories")
+            .and(warp::path("batch"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_memory_store(memory_store.clone()))
+            .and_then(handlers::batch_memories),
+    );
+
     // Clear data endpoint
     let clear_data = warp::path("api").and(warp::path("ego")).and(
         warp::path("clear")
@@ -92,6 +155,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .and_then(handlers::clear_data),
     );
 
+    // Re-seal the STM JSONL file under a new key (see `handlers::rotate_stm_key`
+    // for why this doesn't affect the already-running backend).
+    let rotate_key = warp::path("api").and(warp::path("ego")).and(
+        warp::path("rotate-key")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_string("data/stm.jsonl".to_string()))
+            .and(with_string(config.persistence_mode.clone()))
+            .and_then(handlers::rotate_stm_key),
+    );
+
     // LTM consolidation endpoints
     let consolidate = warp::path("api").and(warp::path("ego")).and(
         warp::path("consolidate")
@@ -99,11 +173,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .and(warp::body::json())
             .and(with_memory_store(memory_store.clone()))
             .and(with_reflection_engine(reflection_engine.clone()))
+            .and(with_embedding_provider(embedding_provider.clone()))
+            .and(with_telemetry(telemetry.clone()))
             .and_then(
                 |request: ConsolidationRequest,
                  memory_store: Arc<RwLock<MemoryStore>>,
-                 reflection_engine: Arc<ReflectionEngine>| {
-                    handlers::consolidate_stm_to_ltm(memory_store, reflection_engine, request)
+                 reflection_engine: Arc<ReflectionEngine>,
+                 embedding_provider: Arc<dyn EmbeddingProvider>,
+                 telemetry: Arc<ego_rs::telemetry::Telemetry>| {
+                    handlers::consolidate_stm_to_ltm(
+                        memory_store,
+                        reflection_engine,
+                        embedding_provider,
+                        request,
+                        telemetry,
+                    )
                 },
             ),
     );
@@ -113,9 +197,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .and(warp::get())
             .and(warp::query())
             .and(with_memory_store(memory_store.clone()))
+            .and(with_embedding_provider(embedding_provider.clone()))
             .and_then(
-                |query: MemoryQuery, memory_store: Arc<RwLock<MemoryStore>>| {
-                    handlers::get_ltm_experiences(memory_store, query)
+                |query: MemoryQuery,
+                 memory_store: Arc<RwLock<MemoryStore>>,
+                 embedding_provider: Arc<dyn EmbeddingProvider>| {
+                    handlers::get_ltm_experiences(memory_store, query, embedding_provider)
                 },
             ),
     );
@@ -137,20 +224,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .and_then(handlers::clear_ltm_data),
     );
 
+    // Targeted deletion: remove a single memory and scrub any LTM experience
+    // that references it, without wiping the rest of the history.
+    let redact_memory = warp::path("api").and(warp::path("ego")).and(
+        warp::path("memories")
+            .and(warp::path!(String))
+            .and(warp::delete())
+            .and(with_memory_store(memory_store.clone()))
+            .and_then(|id: String, memory_store: Arc<RwLock<MemoryStore>>| {
+                handlers::redact_memory(id, memory_store)
+            }),
+    );
+
+    let redact_experience = warp::path("api").and(warp::path("ego")).and(
+        warp::path("experiences")
+            .and(warp::path!(String))
+            .and(warp::delete())
+            .and(with_memory_store(memory_store.clone()))
+            .and_then(|id: String, memory_store: Arc<RwLock<MemoryStore>>| {
+                handlers::redact_experience(id, memory_store)
+            }),
+    );
+
     let routes = health
         .or(status)
         .or(reflect)
+        .or(reflect_stream)
+        .or(memories_poll)
+        .or(memories_batch)
         .or(memories)
         .or(clear_data)
+        .or(rotate_key)
         .or(consolidate)
         .or(ltm_experiences)
         .or(ltm_experience)
         .or(clear_ltm)
+        .or(redact_memory)
+        .or(redact_experience)
         .with(
             warp::cors()
                 .allow_any_origin()
                 .allow_headers(vec!["content-type"])
-                .allow_methods(vec!["GET", "POST"]),
+                .allow_methods(vec!["GET", "POST", "DELETE"]),
         );
 
     info!("Ego service ready");
@@ -170,3 +285,21 @@ fn with_reflection_engine(
 ) -> impl Filter<Extract = (Arc<ReflectionEngine>,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || reflection_engine.clone())
 }
+
+fn with_embedding_provider(
+    embedding_provider: Arc<dyn EmbeddingProvider>,
+) -> impl Filter<Extract = (Arc<dyn EmbeddingProvider>,), Error = std::convert::Infallible> + Clone
+{
+    warp::any().map(move || embedding_provider.clone())
+}
+
+fn with_string(value: String) -> impl Filter<Extract = (String,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || value.clone())
+}
+
+fn with_telemetry(
+    telemetry: Arc<ego_rs::telemetry::Telemetry>,
+) -> impl Filter<Extract = (Arc<ego_rs::telemetry::Telemetry>,), Error = std::convert::Infallible> + Clone
+{
+    warp::any().map(move || telemetry.clone())
+}
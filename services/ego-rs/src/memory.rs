@@ -1,31 +1,126 @@
-use crate::types::{Memory, Modality};
-use chrono::{DateTime, Utc};
+use crate::crypto::EncryptionKey;
+use crate::hnsw::HnswIndex;
+use crate::memory_backend::{JsonlBackend, MemoryBackend};
+use crate::types::{Experience, Memory, Modality};
+use chrono::Utc;
 use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
 
 pub struct MemoryStore {
     memories: HashMap<String, Memory>,
-    file_path: String, // Path to the memory.jsonl file
+    experiences: HashMap<String, Experience>,
+    backend: Box<dyn MemoryBackend>,
+    ltm_file_path: String, // Path to the LTM (ltm.jsonl) file
+    /// Approximate-nearest-neighbor index over `memories`' embeddings, kept
+    /// in sync by `set_embedding`/`redact_memory`/`clear_all_memories` so
+    /// `nearest` doesn't have to linearly scan every memory.
+    hnsw: HnswIndex,
+    /// Notified every time `add_memory`/`add_memory_and_save`/`add_experience`
+    /// mutates the store; `/memories/poll` waits on this instead of
+    /// busy-polling `get_memories` in a loop.
+    changed: Arc<Notify>,
+    /// Epoch-millis timestamp of the most recent mutation — the causality
+    /// token poll clients compare their `since` cursor against.
+    last_mutation_ms: Arc<AtomicI64>,
+    /// When set, `ltm.jsonl` is sealed with this key via
+    /// `EncryptionKey::seal`/`open` instead of written as plaintext JSON.
+    /// STM encryption (if any) is already baked into the configured
+    /// `MemoryBackend` instead.
+    ltm_key: Option<EncryptionKey>,
 }
 
 impl MemoryStore {
     pub fn new() -> Self {
-        Self {
-            memories: HashMap::new(),
-            file_path: "data/memory.jsonl".to_string(), // STM file in ego-rs/data
-        }
+        Self::new_with_backend(Box::new(JsonlBackend::new("data/stm.jsonl".to_string())))
     }
 
     pub fn new_with_path(file_path: String) -> Self {
+        Self::new_with_backend(Box::new(JsonlBackend::new(file_path)))
+    }
+
+    /// Construct with an arbitrary persistence backend, e.g. the
+    /// `SqliteBackend` selected via `Config::memory_backend`.
+    pub fn new_with_backend(backend: Box<dyn MemoryBackend>) -> Self {
         Self {
             memories: HashMap::new(),
-            file_path,
+            experiences: HashMap::new(),
+            backend,
+            ltm_file_path: "data/ltm.jsonl".to_string(),
+            hnsw: HnswIndex::new(),
+            changed: Arc::new(Notify::new()),
+            last_mutation_ms: Arc::new(AtomicI64::new(0)),
+            ltm_key: None,
+        }
+    }
+
+    /// Enable (or disable) encryption-at-rest for `ltm.jsonl`. Existing
+    /// plaintext/differently-keyed records are not migrated automatically.
+    /// `memory_backend::rotate_jsonl_key` re-seals the STM file only; there
+    /// is no counterpart that re-seals `ltm.jsonl`.
+    pub fn set_ltm_encryption_key(&mut self, key: Option<EncryptionKey>) {
+        self.ltm_key = key;
+    }
+
+    /// Record a mutation for `/memories/poll` waiters: bump the causality
+    /// token and wake anyone currently waiting on `changed`.
+    fn mark_mutated(&self) {
+        self.last_mutation_ms
+            .store(Utc::now().timestamp_millis(), Ordering::SeqCst);
+        self.changed.notify_waiters();
+    }
+
+    /// Handles for `/memories/poll` to watch for mutations: a `Notify` to
+    /// wait on and the current causality token, both safe to hold across
+    /// the read-lock release a long-poll loop needs between iterations.
+    pub fn subscribe_changes(&self) -> (Arc<Notify>, Arc<AtomicI64>) {
+        (self.changed.clone(), self.last_mutation_ms.clone())
+    }
+
+    /// Memories created strictly after `since_ms` (epoch millis), oldest
+    /// first — the delta `/memories/poll` returns once new data past the
+    /// client's causality token appears.
+    pub fn memories_since(&self, since_ms: i64) -> Vec<&Memory> {
+        let mut result: Vec<&Memory> = self
+            .memories
+            .values()
+            .filter(|m| m.timestamp.timestamp_millis() > since_ms)
+            .collect();
+        result.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        result
+    }
+
+    /// Experiences consolidated strictly after `since_ms` (epoch millis),
+    /// oldest first. Compares `consolidated_at`, since `created_at` is the
+    /// earliest source thought's time. See [`Self::memories_since`].
+    pub fn experiences_since(&self, since_ms: i64) -> Vec<&Experience> {
+        let mut result: Vec<&Experience> = self
+            .experiences
+            .values()
+            .filter(|e| e.consolidated_at.timestamp_millis() > since_ms)
+            .collect();
+        result.sort_by(|a, b| a.consolidated_at.cmp(&b.consolidated_at));
+        result
+    }
+
+    /// Load every STM memory from the configured backend into the
+    /// in-memory cache.
+    pub fn load_memories(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        for memory in self.backend.load()? {
+            if !memory.embedding.is_empty() {
+                self.hnsw.insert(memory.id.clone(), memory.embedding.clone());
+            }
+            self.memories.insert(memory.id.clone(), memory);
         }
+        Ok(())
     }
 
-    pub fn load_from_jsonl(&mut self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let file = File::open(file_path)?;
+    /// Load LTM experiences from `self.ltm_file_path` (ltm.jsonl).
+    pub fn load_ltm_from_jsonl(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let file = File::open(&self.ltm_file_path)?;
         let reader = BufReader::new(file);
 
         for line in reader.lines() {
@@ -34,143 +129,78 @@ impl MemoryStore {
                 continue;
             }
 
-            let memory_event: serde_json::Value = serde_json::from_str(&line)?;
-
-            let id = memory_event["embedding_id"]
-                .as_str()
-                .unwrap_or("unknown")
-                .to_string();
-            let ts = memory_event["ts"].as_u64().unwrap_or(0) as i64;
-            let source = memory_event["source"].as_str().unwrap_or("text");
-            let facets = memory_event["facets"]
-                .as_object()
-                .unwrap_or(&serde_json::Map::new())
-                .clone();
-
-            let modality = match source {
-                "vision" => Modality::Vision,
-                "speech" => Modality::Speech,
-                "concept" => Modality::Concept,
-                _ => Modality::Text,
+            let experience: Experience = match &self.ltm_key {
+                Some(key) => serde_json::from_slice(&key.open(&line)?)?,
+                None => serde_json::from_str(&line)?,
             };
-
-            // Handle both unconsolidated (from sentience-rs) and consolidated (from ego-rs) memories
-            let memory = Memory {
-                id: id.clone(),
-                timestamp: DateTime::from_timestamp(ts, 0).unwrap_or_else(|| Utc::now()),
-                modality,
-                embedding: memory_event["embedding"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_f64())
-                            .map(|f| f as f32)
-                            .collect()
-                    })
-                    .unwrap_or_default(),
-                content: memory_event["content"].as_str().unwrap_or("").to_string(),
-                facets: facets.into_iter().map(|(k, v)| (k, v)).collect(),
-                tags: memory_event["tags"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_str())
-                            .map(|s| s.to_string())
-                            .collect()
-                    })
-                    .unwrap_or_default(),
-            };
-
-            self.memories.insert(id, memory);
+            self.experiences.insert(experience.id.clone(), experience);
         }
 
         Ok(())
     }
 
-    pub fn load_ltm_from_jsonl(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let file_path = self.file_path.clone();
-        self.load_from_jsonl(&file_path)
-    }
-
-    pub fn save_to_jsonl(&self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Write every experience to `self.ltm_file_path`, overwriting it.
+    /// Sealed per-line under `self.ltm_key` when encryption is enabled,
+    /// plaintext JSON otherwise.
+    pub fn save_ltm_to_jsonl(&self) -> Result<(), Box<dyn std::error::Error>> {
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
-            .open(&self.file_path)?;
+            .open(&self.ltm_file_path)?;
 
-        for memory in self.memories.values() {
-            // Convert Memory to the format expected by sentience-rs
-            let memory_event = serde_json::json!({
-                "embedding_id": memory.id,
-                "ts": memory.timestamp.timestamp(),
-                "source": match memory.modality {
-                    Modality::Vision => "vision",
-                    Modality::Speech => "speech",
-                    Modality::Text => "text",
-                    Modality::Concept => "concept",
-                },
-                "facets": memory.facets,
-                "content": memory.content,
-                "tags": memory.tags,
-                "embedding": memory.embedding
-            });
-
-            writeln!(file, "{}", serde_json::to_string(&memory_event)?)?;
+        for experience in self.experiences.values() {
+            let line = match &self.ltm_key {
+                Some(key) => key.seal(&serde_json::to_vec(experience)?)?,
+                None => serde_json::to_string(experience)?,
+            };
+            writeln!(file, "{}", line)?;
         }
 
         Ok(())
     }
 
-    pub fn append_memory_to_jsonl(
-        &self,
-        memory: &Memory,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.file_path)?;
-
-        let memory_event = serde_json::json!({
-            "embedding_id": memory.id,
-            "ts": memory.timestamp.timestamp(),
-            "source": match memory.modality {
-                Modality::Vision => "vision",
-                Modality::Speech => "speech",
-                Modality::Text => "text",
-                Modality::Concept => "concept",
-            },
-            "facets": memory.facets,
-            "content": memory.content,
-            "tags": memory.tags,
-            "embedding": memory.embedding
-        });
-
-        writeln!(file, "{}", serde_json::to_string(&memory_event)?)?;
-        Ok(())
-    }
-
     pub fn add_memory(&mut self, memory: Memory) {
+        if !memory.embedding.is_empty() {
+            self.hnsw.insert(memory.id.clone(), memory.embedding.clone());
+        }
         self.memories.insert(memory.id.clone(), memory);
+        self.mark_mutated();
     }
 
     pub fn add_memory_and_save(
         &mut self,
         memory: Memory,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        if !memory.embedding.is_empty() {
+            self.hnsw.insert(memory.id.clone(), memory.embedding.clone());
+        }
         self.memories.insert(memory.id.clone(), memory.clone());
-        self.append_memory_to_jsonl(&memory)?;
-        Ok(())
+        self.mark_mutated();
+        self.backend.append(&memory)
     }
 
+    /// Persist the full in-memory set through the backend. For
+    /// `SqliteBackend` each record is an indexed upsert; `JsonlBackend` still
+    /// has to rewrite the whole file, same as before this was backend-agnostic.
     pub fn save_all_memories(&self) -> Result<(), Box<dyn std::error::Error>> {
-        self.save_to_jsonl()
+        for memory in self.memories.values() {
+            self.backend.update(memory)?;
+        }
+        Ok(())
     }
 
     pub fn get_memory(&self, id: &str) -> Option<&Memory> {
         self.memories.get(id)
     }
 
+    /// Where `id`'s content came from, if it's locatable — lets a UI jump
+    /// from a retrieved memory back to the original transcript, image
+    /// region, or document span that produced it.
+    pub fn locate(&self, id: &str) -> Option<&crate::types::SourceRef> {
+        self.memories.get(id)?.source_ref.as_ref()
+    }
+
     pub fn get_recent_memories(&self, limit: usize, time_window_minutes: u64) -> Vec<&Memory> {
         let cutoff = Utc::now() - chrono::Duration::minutes(time_window_minutes as i64);
 
@@ -195,6 +225,101 @@ impl MemoryStore {
     pub fn get_all_memories(&self) -> Vec<&Memory> {
         self.memories.values().collect()
     }
+
+    pub fn clear_all_memories(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.backend.clear()?;
+        for id in self.memories.keys().cloned().collect::<Vec<_>>() {
+            self.hnsw.remove(&id);
+        }
+        self.memories.clear();
+        Ok(())
+    }
+
+    pub fn add_experience(&mut self, experience: Experience) {
+        self.experiences.insert(experience.id.clone(), experience);
+        self.mark_mutated();
+    }
+
+    pub fn get_experiences(&self) -> Vec<&Experience> {
+        self.experiences.values().collect()
+    }
+
+    pub fn get_experience(&self, id: &str) -> Option<&Experience> {
+        self.experiences.get(id)
+    }
+
+    pub fn clear_all_experiences(&mut self) {
+        self.experiences.clear();
+    }
+
+    /// Remove a memory by id — a single indexed delete on `SqliteBackend`,
+    /// a full-file rewrite on `JsonlBackend` — and scrub it from any
+    /// experience's `consolidated_from` provenance list so LTM doesn't keep
+    /// a dangling reference to a now-deleted STM record. Returns whether the
+    /// memory existed. Call `save_ltm_to_jsonl` afterward to persist the
+    /// provenance scrub.
+    pub fn redact_memory(&mut self, id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let existed = self.memories.remove(id).is_some();
+        if existed {
+            self.backend.delete(id)?;
+            self.hnsw.remove(id);
+        }
+
+        for experience in self.experiences.values_mut() {
+            experience.consolidated_from.retain(|ref_id| ref_id != id);
+        }
+
+        Ok(existed)
+    }
+
+    /// Remove an experience by id. Returns whether it existed. Does not
+    /// rewrite `ltm.jsonl`; call `save_ltm_to_jsonl` afterward to persist
+    /// the redaction.
+    pub fn redact_experience(&mut self, id: &str) -> bool {
+        self.experiences.remove(id).is_some()
+    }
+
+    /// Cache an LLM-assigned poignancy rating (1-10) on a stored memory so
+    /// future retrieval scoring doesn't re-query the model for it.
+    pub fn set_poignancy(&mut self, id: &str, poignancy: u8) {
+        if let Some(memory) = self.memories.get_mut(id) {
+            memory
+                .facets
+                .insert("memory.poignancy".to_string(), serde_json::json!(poignancy));
+        }
+    }
+
+    /// Record the embedding computed for a stored memory's content, so
+    /// semantic search has a vector to compare against.
+    pub fn set_embedding(&mut self, id: &str, embedding: Vec<f32>) {
+        if let Some(memory) = self.memories.get_mut(id) {
+            memory.embedding = embedding.clone();
+            self.hnsw.insert(id.to_string(), embedding);
+        }
+    }
+
+    /// Approximate cosine k-NN via the in-memory HNSW index, falling back to
+    /// a linear scan if the index is empty.
+    pub fn nearest(&self, query_embedding: &[f32], limit: usize) -> Vec<&Memory> {
+        if self.hnsw.is_empty() {
+            let mut scored: Vec<(f32, &Memory)> = self
+                .memories
+                .values()
+                .filter(|m| !m.embedding.is_empty())
+                .map(|m| (cosine_similarity(&m.embedding, query_embedding), m))
+                .collect();
+
+            scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+            scored.truncate(limit);
+            return scored.into_iter().map(|(_, m)| m).collect();
+        }
+
+        self.hnsw
+            .search(query_embedding, limit)
+            .into_iter()
+            .filter_map(|(id, _)| self.memories.get(&id))
+            .collect()
+    }
 }
 
 // Helper functions for memory selection and processing
@@ -214,25 +339,135 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     }
 }
 
+/// BM25 term frequency saturation constant.
+const BM25_K1: f32 = 1.5;
+/// BM25 document-length normalization constant.
+const BM25_B: f32 = 0.75;
+
+/// Mean/std used to shift+scale cosine similarity onto the same footing as
+/// the BM25 keyword score before blending. Rough corpus-agnostic guesses —
+/// cosine similarity between a focus embedding and an unrelated memory
+/// clusters near 0, related memories trend higher.
+const COSINE_SCORE_MEAN: f32 = 0.3;
+const COSINE_SCORE_STD: f32 = 0.2;
+/// Mean/std for BM25 scores over short memory contents, where a couple of
+/// matched terms is already a strong signal.
+const KEYWORD_SCORE_MEAN: f32 = 1.5;
+const KEYWORD_SCORE_STD: f32 = 1.5;
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split_whitespace()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Shift by `mean`, scale by `std_dev`, then clamp to `[0, 1]` so cosine and
+/// BM25 scores — which live on very different scales — can be blended with
+/// a single `semantic_ratio` weight.
+fn normalize_score(score: f32, mean: f32, std_dev: f32) -> f32 {
+    if std_dev <= 0.0 {
+        return 0.0;
+    }
+    ((score - mean) / std_dev).clamp(0.0, 1.0)
+}
+
+/// BM25 score of `query_terms` against one document's tokens, given corpus
+/// statistics (`avg_doc_len`, `doc_freq`, `num_docs`) computed over the
+/// candidate set passed to `select_relevant_memories` — there's no separate
+/// persistent corpus index to draw global statistics from.
+fn bm25_score(
+    query_terms: &[String],
+    doc_tokens: &[String],
+    avg_doc_len: f32,
+    doc_freq: &HashMap<String, usize>,
+    num_docs: usize,
+) -> f32 {
+    if avg_doc_len <= 0.0 {
+        return 0.0;
+    }
+    let doc_len = doc_tokens.len() as f32;
+    let mut score = 0.0;
+    for term in query_terms {
+        let term_freq = doc_tokens.iter().filter(|t| *t == term).count() as f32;
+        if term_freq == 0.0 {
+            continue;
+        }
+        let df = *doc_freq.get(term).unwrap_or(&0) as f32;
+        let idf = ((num_docs as f32 - df + 0.5) / (df + 0.5) + 1.0).ln();
+        let numerator = term_freq * (BM25_K1 + 1.0);
+        let denominator =
+            term_freq + BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avg_doc_len);
+        score += idf * numerator / denominator;
+    }
+    score
+}
+
+/// Hybrid keyword + semantic ranking, then the same per-modality diversity
+/// caps this function has always applied. `semantic_ratio` blends a
+/// normalized cosine-similarity score (1.0 = pure vector) with a normalized
+/// BM25-style keyword score over `content`/`tags` (0.0 = pure keyword):
+/// `semantic_ratio * norm_cosine + (1 - semantic_ratio) * norm_keyword`.
+/// With neither `focus_embedding` nor `query` given, falls back to the
+/// original recency sort.
 pub fn select_relevant_memories<'a>(
     memories: &'a [&'a Memory],
     focus_embedding: Option<&'a [f32]>,
+    query: Option<&str>,
+    semantic_ratio: f32,
     max_count: usize,
 ) -> Vec<&'a Memory> {
     let mut selected = memories.to_vec();
 
-    // Sort by relevance if focus embedding is provided
-    if let Some(focus) = focus_embedding {
-        selected.sort_by(|a, b| {
-            let score_a = cosine_similarity(&a.embedding, focus);
-            let score_b = cosine_similarity(&b.embedding, focus);
-            score_b
-                .partial_cmp(&score_a)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-    } else {
-        // Sort by recency
+    if focus_embedding.is_none() && query.is_none() {
         selected.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    } else {
+        let query_terms = query.map(tokenize).unwrap_or_default();
+        let doc_tokens: Vec<Vec<String>> = selected
+            .iter()
+            .map(|m| tokenize(&format!("{} {}", m.content, m.tags.join(" "))))
+            .collect();
+
+        let avg_doc_len = if doc_tokens.is_empty() {
+            0.0
+        } else {
+            doc_tokens.iter().map(|t| t.len()).sum::<usize>() as f32 / doc_tokens.len() as f32
+        };
+
+        let mut doc_freq: HashMap<String, usize> = HashMap::new();
+        if !query_terms.is_empty() {
+            for tokens in &doc_tokens {
+                let unique: std::collections::HashSet<&String> = tokens.iter().collect();
+                for term in &query_terms {
+                    if unique.contains(term) {
+                        *doc_freq.entry(term.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut scored: Vec<(f32, &Memory)> = selected
+            .iter()
+            .zip(doc_tokens.iter())
+            .map(|(&memory, tokens)| {
+                let cosine = focus_embedding
+                    .map_or(0.0, |focus| cosine_similarity(&memory.embedding, focus));
+                let keyword = if query_terms.is_empty() {
+                    0.0
+                } else {
+                    bm25_score(&query_terms, tokens, avg_doc_len, &doc_freq, selected.len())
+                };
+
+                let norm_cosine = normalize_score(cosine, COSINE_SCORE_MEAN, COSINE_SCORE_STD);
+                let norm_keyword = normalize_score(keyword, KEYWORD_SCORE_MEAN, KEYWORD_SCORE_STD);
+                let blended = semantic_ratio * norm_cosine + (1.0 - semantic_ratio) * norm_keyword;
+                (blended, memory)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        selected = scored.into_iter().map(|(_, m)| m).collect();
     }
 
     // Ensure diversity by modality
@@ -262,3 +497,96 @@ pub fn select_relevant_memories<'a>(
     result.truncate(max_count);
     result
 }
+
+/// Relative weight of each retrieval component in [`retrieve_top_k`]. Mirrors
+/// the generative-agents retrieval function: `score = w_r*recency +
+/// w_i*importance + w_v*relevance`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetrievalWeights {
+    pub recency: f32,
+    pub importance: f32,
+    pub relevance: f32,
+}
+
+impl Default for RetrievalWeights {
+    fn default() -> Self {
+        Self {
+            recency: 1.0,
+            importance: 1.0,
+            relevance: 1.0,
+        }
+    }
+}
+
+/// Decay applied to recency per hour since a memory's timestamp.
+const RECENCY_DECAY_PER_HOUR: f32 = 0.995;
+
+/// Poignancy rating (1-10) cached on a memory's facets, or the neutral
+/// midpoint if it hasn't been rated yet.
+pub fn poignancy_of(memory: &Memory) -> f32 {
+    memory
+        .facets
+        .get("memory.poignancy")
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32)
+        .unwrap_or(5.0)
+}
+
+fn min_max_normalize(values: &[f32]) -> Vec<f32> {
+    let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+    if !(max - min).is_finite() || (max - min).abs() < f32::EPSILON {
+        return values.iter().map(|_| 1.0).collect();
+    }
+
+    values.iter().map(|&v| (v - min) / (max - min)).collect()
+}
+
+/// Generative-agents style retrieval: score each candidate by recency decay,
+/// cached importance (poignancy), and relevance to `focus_embedding`, min-max
+/// normalize each component across the candidate set, blend by `weights`, and
+/// return the top `k`.
+pub fn retrieve_top_k<'a>(
+    memories: &[&'a Memory],
+    focus_embedding: Option<&[f32]>,
+    k: usize,
+    weights: RetrievalWeights,
+) -> Vec<&'a Memory> {
+    if memories.is_empty() {
+        return Vec::new();
+    }
+
+    let now = Utc::now();
+    let recency: Vec<f32> = memories
+        .iter()
+        .map(|m| {
+            let hours = (now - m.timestamp).num_seconds().max(0) as f32 / 3600.0;
+            RECENCY_DECAY_PER_HOUR.powf(hours)
+        })
+        .collect();
+    let importance: Vec<f32> = memories.iter().map(|m| poignancy_of(m)).collect();
+    let relevance: Vec<f32> = memories
+        .iter()
+        .map(|m| focus_embedding.map_or(0.0, |focus| cosine_similarity(&m.embedding, focus)))
+        .collect();
+
+    let recency_n = min_max_normalize(&recency);
+    let importance_n = min_max_normalize(&importance);
+    let relevance_n = min_max_normalize(&relevance);
+
+    let mut scored: Vec<(f32, &Memory)> = memories
+        .iter()
+        .enumerate()
+        .map(|(i, &m)| {
+            let score = weights.recency * recency_n[i]
+                + weights.importance * importance_n[i]
+                + weights.relevance * relevance_n[i];
+            (score, m)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(k);
+    scored.into_iter().map(|(_, m)| m).collect()
+}
@@ -0,0 +1,567 @@
+use crate::crypto::EncryptionKey;
+use crate::types::{Memory, Modality, SourceRef};
+use chrono::{DateTime, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::Mutex;
+
+/// Filter applied by `MemoryBackend::query`. Every `Some` field is an AND'd
+/// constraint; leave a field `None` to skip it.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFilter {
+    pub id: Option<String>,
+    pub modality: Option<Modality>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Persistence for `Memory` records, independent of storage format.
+/// `MemoryStore` keeps its own in-memory cache for fast reads (`nearest`,
+/// `select_relevant_memories`, ...); a backend only needs to make mutations
+/// durable and to answer indexed lookups for callers that want to bypass
+/// that cache.
+pub trait MemoryBackend: Send + Sync {
+    /// Load every stored memory, e.g. at startup.
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>>;
+    /// Persist a newly created memory.
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>>;
+    /// Persist a change to an existing memory (or insert it, if absent).
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>>;
+    /// Remove a memory by id. A no-op if it isn't present.
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>>;
+    /// Indexed lookup by id/modality/time without loading everything.
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>>;
+
+    /// Remove every stored memory. The default deletes each one individually;
+    /// backends that can express this as a single durable operation (e.g.
+    /// `OpLogBackend`'s `Clear` record) should override it.
+    fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for memory in self.load()? {
+            self.delete(&memory.id)?;
+        }
+        Ok(())
+    }
+}
+
+fn modality_str(modality: &Modality) -> &'static str {
+    match modality {
+        Modality::Vision => "vision",
+        Modality::Speech => "speech",
+        Modality::Text => "text",
+        Modality::Concept => "concept",
+    }
+}
+
+fn modality_from_str(s: &str) -> Modality {
+    match s {
+        "vision" => Modality::Vision,
+        "speech" => Modality::Speech,
+        "concept" => Modality::Concept,
+        _ => Modality::Text,
+    }
+}
+
+pub(crate) fn matches_filter(memory: &Memory, filter: &MemoryFilter) -> bool {
+    if let Some(id) = &filter.id {
+        if &memory.id != id {
+            return false;
+        }
+    }
+    if let Some(modality) = &filter.modality {
+        if std::mem::discriminant(&memory.modality) != std::mem::discriminant(modality) {
+            return false;
+        }
+    }
+    if let Some(since) = filter.since {
+        if memory.timestamp < since {
+            return false;
+        }
+    }
+    true
+}
+
+fn source_ref_to_json(source_ref: &SourceRef) -> serde_json::Value {
+    serde_json::json!({
+        "uri": source_ref.uri,
+        "start": source_ref.start,
+        "end": source_ref.end,
+        "chunk_index": source_ref.chunk_index,
+    })
+}
+
+fn source_ref_from_json(value: &serde_json::Value) -> Option<SourceRef> {
+    Some(SourceRef {
+        uri: value["uri"].as_str()?.to_string(),
+        start: value["start"].as_u64()? as usize,
+        end: value["end"].as_u64()? as usize,
+        chunk_index: value["chunk_index"].as_u64()? as usize,
+    })
+}
+
+fn memory_to_json(memory: &Memory) -> serde_json::Value {
+    serde_json::json!({
+        "embedding_id": memory.id,
+        "ts": memory.timestamp.timestamp(),
+        "source": modality_str(&memory.modality),
+        "facets": memory.facets,
+        "content": memory.content,
+        "tags": memory.tags,
+        "embedding": memory.embedding,
+        // Absent (rather than `null`) on legacy lines; `source_ref_from_json`
+        // returning `None` for a missing key reads the same as a `null` one.
+        "source_ref": memory.source_ref.as_ref().map(source_ref_to_json),
+    })
+}
+
+fn memory_from_json(value: &serde_json::Value) -> Memory {
+    let id = value["embedding_id"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_string();
+    let ts = value["ts"].as_u64().unwrap_or(0) as i64;
+    let facets = value["facets"].as_object().cloned().unwrap_or_default();
+
+    Memory {
+        id,
+        timestamp: DateTime::from_timestamp(ts, 0).unwrap_or_else(Utc::now),
+        modality: modality_from_str(value["source"].as_str().unwrap_or("text")),
+        embedding: value["embedding"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_f64())
+                    .map(|f| f as f32)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        content: value["content"].as_str().unwrap_or("").to_string(),
+        facets: facets.into_iter().collect(),
+        tags: value["tags"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        source_ref: source_ref_from_json(&value["source_ref"]),
+    }
+}
+
+/// Append-only JSONL file, the zero-dependency default `MemoryStore` has
+/// always used. JSONL has no way to edit a line in place, so `update` and
+/// `delete` read the whole file, patch the in-memory set, and rewrite it.
+pub struct JsonlBackend {
+    file_path: String,
+}
+
+impl JsonlBackend {
+    pub fn new(file_path: String) -> Self {
+        Self { file_path }
+    }
+
+    fn read_all(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let file = match File::open(&self.file_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let reader = BufReader::new(file);
+
+        let mut memories = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            memories.push(memory_from_json(&serde_json::from_str(&line)?));
+        }
+        Ok(memories)
+    }
+
+    fn write_all(&self, memories: &[Memory]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.file_path)?;
+
+        for memory in memories {
+            writeln!(file, "{}", serde_json::to_string(&memory_to_json(memory))?)?;
+        }
+        Ok(())
+    }
+}
+
+impl MemoryBackend for JsonlBackend {
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        self.read_all()
+    }
+
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)?;
+        writeln!(file, "{}", serde_json::to_string(&memory_to_json(memory))?)?;
+        Ok(())
+    }
+
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        let mut memories = self.read_all()?;
+        match memories.iter_mut().find(|m| m.id == memory.id) {
+            Some(existing) => *existing = memory.clone(),
+            None => memories.push(memory.clone()),
+        }
+        self.write_all(&memories)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut memories = self.read_all()?;
+        memories.retain(|m| m.id != id);
+        self.write_all(&memories)
+    }
+
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        Ok(self
+            .read_all()?
+            .into_iter()
+            .filter(|m| matches_filter(m, filter))
+            .collect())
+    }
+}
+
+/// Same on-disk layout and full-file-rewrite behavior as `JsonlBackend`, but
+/// each line is an AEAD-sealed blob (nonce + ciphertext, base64-encoded)
+/// instead of plaintext JSON — protects `Memory.content` (transcribed
+/// speech/vision descriptions) at rest. Transparent to callers: `load`
+/// decrypts, `append`/`update`/`delete` re-encrypt, same as the plaintext
+/// backend.
+pub struct EncryptedJsonlBackend {
+    file_path: String,
+    key: EncryptionKey,
+}
+
+impl EncryptedJsonlBackend {
+    pub fn new(file_path: String, key: EncryptionKey) -> Self {
+        Self { file_path, key }
+    }
+
+    fn read_all(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let file = match File::open(&self.file_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let reader = BufReader::new(file);
+
+        let mut memories = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let plaintext = self.key.open(&line)?;
+            memories.push(memory_from_json(&serde_json::from_slice(&plaintext)?));
+        }
+        Ok(memories)
+    }
+
+    fn write_all(&self, memories: &[Memory]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.file_path)?;
+
+        for memory in memories {
+            let plaintext = serde_json::to_vec(&memory_to_json(memory))?;
+            writeln!(file, "{}", self.key.seal(&plaintext)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl MemoryBackend for EncryptedJsonlBackend {
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        self.read_all()
+    }
+
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)?;
+        let plaintext = serde_json::to_vec(&memory_to_json(memory))?;
+        writeln!(file, "{}", self.key.seal(&plaintext)?)?;
+        Ok(())
+    }
+
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        let mut memories = self.read_all()?;
+        match memories.iter_mut().find(|m| m.id == memory.id) {
+            Some(existing) => *existing = memory.clone(),
+            None => memories.push(memory.clone()),
+        }
+        self.write_all(&memories)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut memories = self.read_all()?;
+        memories.retain(|m| m.id != id);
+        self.write_all(&memories)
+    }
+
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        Ok(self
+            .read_all()?
+            .into_iter()
+            .filter(|m| matches_filter(m, filter))
+            .collect())
+    }
+}
+
+/// Re-seal every record in an encrypted JSONL file under `new_key`,
+/// decrypting with `old_key` first. The on-disk line format (one sealed
+/// blob per line) is unchanged, only the key the blobs were sealed under.
+pub fn rotate_jsonl_key(
+    file_path: &str,
+    old_key: &EncryptionKey,
+    new_key: &EncryptionKey,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let memories = EncryptedJsonlBackend::new(file_path.to_string(), old_key.clone()).read_all()?;
+    EncryptedJsonlBackend::new(file_path.to_string(), new_key.clone()).write_all(&memories)?;
+    Ok(memories.len())
+}
+
+/// SQLite-backed store: indexed lookups by id/modality/time and
+/// transactional single-record updates, without rewriting the whole table
+/// on every change the way `JsonlBackend` has to.
+pub struct SqliteBackend {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteBackend {
+    pub fn new(db_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let conn = rusqlite::Connection::open(db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS memories (
+                id TEXT PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                modality TEXT NOT NULL,
+                content TEXT NOT NULL,
+                facets TEXT NOT NULL,
+                tags TEXT NOT NULL,
+                embedding TEXT NOT NULL,
+                source_ref TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_memories_modality ON memories(modality);
+            CREATE INDEX IF NOT EXISTS idx_memories_timestamp ON memories(timestamp);",
+        )?;
+        // Absent on databases created before `source_ref` existed; ignore the
+        // "duplicate column" error `ALTER TABLE` raises when it's already there.
+        let _ = conn.execute_batch("ALTER TABLE memories ADD COLUMN source_ref TEXT");
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn row_to_memory(row: &rusqlite::Row) -> rusqlite::Result<Memory> {
+        let facets_json: String = row.get("facets")?;
+        let tags_json: String = row.get("tags")?;
+        let embedding_json: String = row.get("embedding")?;
+        let modality: String = row.get("modality")?;
+        let source_ref_json: Option<String> = row.get("source_ref")?;
+
+        Ok(Memory {
+            id: row.get("id")?,
+            timestamp: DateTime::from_timestamp(row.get("timestamp")?, 0).unwrap_or_else(Utc::now),
+            modality: modality_from_str(&modality),
+            embedding: serde_json::from_str(&embedding_json).unwrap_or_default(),
+            content: row.get("content")?,
+            facets: serde_json::from_str(&facets_json).unwrap_or_default(),
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            source_ref: source_ref_json.and_then(|s| serde_json::from_str(&s).ok()),
+        })
+    }
+}
+
+impl MemoryBackend for SqliteBackend {
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM memories")?;
+        Ok(stmt
+            .query_map([], Self::row_to_memory)?
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO memories (id, timestamp, modality, content, facets, tags, embedding, source_ref)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                memory.id,
+                memory.timestamp.timestamp(),
+                modality_str(&memory.modality),
+                memory.content,
+                serde_json::to_string(&memory.facets)?,
+                serde_json::to_string(&memory.tags)?,
+                serde_json::to_string(&memory.embedding)?,
+                memory
+                    .source_ref
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        // Same statement as `append`: a single indexed upsert, no table scan.
+        self.append(memory)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM memories WHERE id = ?1", rusqlite::params![id])?;
+        Ok(())
+    }
+
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut sql = "SELECT * FROM memories WHERE 1=1".to_string();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(id) = &filter.id {
+            sql.push_str(" AND id = ?");
+            params.push(Box::new(id.clone()));
+        }
+        if let Some(modality) = &filter.modality {
+            sql.push_str(" AND modality = ?");
+            params.push(Box::new(modality_str(modality).to_string()));
+        }
+        if let Some(since) = filter.since {
+            sql.push_str(" AND timestamp >= ?");
+            params.push(Box::new(since.timestamp()));
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        Ok(stmt
+            .query_map(param_refs.as_slice(), Self::row_to_memory)?
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+/// Embedded key-value store keyed by memory UUID: `append`/`update`/`delete`
+/// touch a single key each, so large stores don't pay a full-file rewrite
+/// the way `JsonlBackend` does on every mutation. Unlike `SqliteBackend`,
+/// sled has no secondary indexes, so `query` falls back to a full scan.
+pub struct SledBackend {
+    db: sled::Db,
+}
+
+impl SledBackend {
+    pub fn new(db_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let db = sled::open(db_path)?;
+        Ok(Self { db })
+    }
+}
+
+impl MemoryBackend for SledBackend {
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        let mut memories = Vec::new();
+        for entry in self.db.iter() {
+            let (_, value) = entry?;
+            memories.push(memory_from_json(&serde_json::from_slice(&value)?));
+        }
+        Ok(memories)
+    }
+
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        self.db.insert(
+            memory.id.as_bytes(),
+            serde_json::to_vec(&memory_to_json(memory))?,
+        )?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        // Keyed by id: an insert on an existing key overwrites it in place.
+        self.append(memory)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.db.remove(id.as_bytes())?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter(|m| matches_filter(m, filter))
+            .collect())
+    }
+}
+
+/// Build the configured `MemoryBackend`: `memory_backend = "sqlite"` or
+/// `"sled"` opens (or creates) `memory_db_path`; `"oplog"` opens (or
+/// creates) the log+checkpoint pair at `memory_log_path`; anything else
+/// keeps the JSONL default at `jsonl_path`, sealed with
+/// `config.encryption_key` when `config.persistence_mode = "encrypted"`.
+pub fn from_config(config: &crate::config::Config, jsonl_path: String) -> Box<dyn MemoryBackend> {
+    match config.memory_backend.as_str() {
+        "sqlite" => match SqliteBackend::new(&config.memory_db_path) {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to open SQLite memory backend at {}: {}; falling back to JSONL",
+                    config.memory_db_path,
+                    e
+                );
+                Box::new(JsonlBackend::new(jsonl_path))
+            }
+        },
+        "sled" => match SledBackend::new(&config.memory_db_path) {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to open sled memory backend at {}: {}; falling back to JSONL",
+                    config.memory_db_path,
+                    e
+                );
+                Box::new(JsonlBackend::new(jsonl_path))
+            }
+        },
+        "oplog" => match crate::oplog::OpLogBackend::new(&config.memory_log_path) {
+            Ok(backend) => Box::new(backend),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to open op-log memory backend at {}: {}; falling back to JSONL",
+                    config.memory_log_path,
+                    e
+                );
+                Box::new(JsonlBackend::new(jsonl_path))
+            }
+        },
+        _ if config.persistence_mode == "encrypted" => match config
+            .encryption_key
+            .as_deref()
+            .ok_or_else(|| "persistence_mode=encrypted but no encryption_key set".to_string())
+            .and_then(|hex| EncryptionKey::from_hex(hex).map_err(|e| e.to_string()))
+        {
+            Ok(key) => Box::new(EncryptedJsonlBackend::new(jsonl_path, key)),
+            Err(e) => {
+                tracing::error!("{}; falling back to plaintext JSONL", e);
+                Box::new(JsonlBackend::new(jsonl_path))
+            }
+        },
+        _ => Box::new(JsonlBackend::new(jsonl_path)),
+    }
+}
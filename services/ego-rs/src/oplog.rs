@@ -0,0 +1,227 @@
+use crate::memory_backend::{matches_filter, MemoryBackend, MemoryFilter};
+use crate::types::Memory;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::Mutex;
+
+/// How many operations accumulate in the log before a full-state checkpoint
+/// is written and the log truncated.
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 64;
+
+/// A durable mutation to the memory set, as appended to the operation log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum Op {
+    AddMemory(Memory),
+    UpdateMemory(Memory),
+    DeleteMemory { id: String },
+    Clear,
+}
+
+/// One line of the log file: an `Op` plus the strictly increasing
+/// timestamp that orders replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OpRecord {
+    timestamp: i64,
+    op: Op,
+}
+
+/// Full-state snapshot written every `checkpoint_interval` operations.
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    timestamp: i64,
+    memories: Vec<Memory>,
+}
+
+struct OpLogState {
+    memories: HashMap<String, Memory>,
+    last_timestamp: i64,
+    ops_since_checkpoint: u64,
+}
+
+fn apply(memories: &mut HashMap<String, Memory>, op: &Op) {
+    match op {
+        Op::AddMemory(memory) | Op::UpdateMemory(memory) => {
+            memories.insert(memory.id.clone(), memory.clone());
+        }
+        Op::DeleteMemory { id } => {
+            memories.remove(id);
+        }
+        Op::Clear => memories.clear(),
+    }
+}
+
+/// Bayou-style log+checkpoint persistence: every mutation is appended to
+/// `log_path` as a small `OpRecord` and fsynced before the call returns, so
+/// a crash can lose at most the write in flight. A full-state `Checkpoint`
+/// is written to `checkpoint_path` every `checkpoint_interval` operations
+/// and the log truncated, so recovery never has to replay further back than
+/// the last checkpoint — bounded recovery time instead of `JsonlBackend`'s
+/// whole-file rewrite on every mutation.
+pub struct OpLogBackend {
+    log_path: String,
+    checkpoint_path: String,
+    checkpoint_interval: u64,
+    state: Mutex<OpLogState>,
+}
+
+impl OpLogBackend {
+    pub fn new(log_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_checkpoint_interval(log_path, DEFAULT_CHECKPOINT_INTERVAL)
+    }
+
+    pub fn with_checkpoint_interval(
+        log_path: &str,
+        checkpoint_interval: u64,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let backend = Self {
+            log_path: log_path.to_string(),
+            checkpoint_path: format!("{}.checkpoint", log_path),
+            checkpoint_interval,
+            state: Mutex::new(OpLogState {
+                memories: HashMap::new(),
+                last_timestamp: 0,
+                ops_since_checkpoint: 0,
+            }),
+        };
+        backend.recover()?;
+        Ok(backend)
+    }
+
+    /// Load the most recent checkpoint (if any), then replay every log
+    /// entry whose timestamp is strictly greater than it, reconstructing
+    /// the in-memory mirror that `load`/`query` read from.
+    fn recover(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Ok(contents) = std::fs::read_to_string(&self.checkpoint_path) {
+            if !contents.trim().is_empty() {
+                let checkpoint: Checkpoint = serde_json::from_str(&contents)?;
+                state.last_timestamp = checkpoint.timestamp;
+                for memory in checkpoint.memories {
+                    state.memories.insert(memory.id.clone(), memory);
+                }
+            }
+        }
+
+        let file = match File::open(&self.log_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let checkpoint_timestamp = state.last_timestamp;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: OpRecord = serde_json::from_str(&line)?;
+            if record.timestamp <= checkpoint_timestamp {
+                continue;
+            }
+            apply(&mut state.memories, &record.op);
+            state.last_timestamp = record.timestamp;
+            state.ops_since_checkpoint += 1;
+        }
+
+        Ok(())
+    }
+
+    /// At least the wall clock and strictly greater than the last timestamp
+    /// handed out, so replay order stays deterministic even for ops
+    /// appended within the same millisecond.
+    fn next_timestamp(last: i64) -> i64 {
+        (Utc::now().timestamp_millis()).max(last + 1)
+    }
+
+    fn append_op(&self, op: Op) -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = self.state.lock().unwrap();
+        let timestamp = Self::next_timestamp(state.last_timestamp);
+        let record = OpRecord { timestamp, op };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        file.sync_all()?;
+
+        apply(&mut state.memories, &record.op);
+        state.last_timestamp = timestamp;
+        state.ops_since_checkpoint += 1;
+
+        if state.ops_since_checkpoint >= self.checkpoint_interval {
+            self.write_checkpoint(&state)?;
+            state.ops_since_checkpoint = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Serialize the mirror as of `state.last_timestamp` to
+    /// `checkpoint_path`, then truncate the log: every entry up to here is
+    /// now subsumed by the checkpoint.
+    fn write_checkpoint(&self, state: &OpLogState) -> Result<(), Box<dyn std::error::Error>> {
+        let checkpoint = Checkpoint {
+            timestamp: state.last_timestamp,
+            memories: state.memories.values().cloned().collect(),
+        };
+        let mut checkpoint_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.checkpoint_path)?;
+        writeln!(checkpoint_file, "{}", serde_json::to_string(&checkpoint)?)?;
+        checkpoint_file.sync_all()?;
+
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.log_path)?
+            .sync_all()?;
+
+        Ok(())
+    }
+}
+
+impl MemoryBackend for OpLogBackend {
+    fn load(&self) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .memories
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    fn append(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        self.append_op(Op::AddMemory(memory.clone()))
+    }
+
+    fn update(&self, memory: &Memory) -> Result<(), Box<dyn std::error::Error>> {
+        self.append_op(Op::UpdateMemory(memory.clone()))
+    }
+
+    fn delete(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.append_op(Op::DeleteMemory { id: id.to_string() })
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.append_op(Op::Clear)
+    }
+
+    fn query(&self, filter: &MemoryFilter) -> Result<Vec<Memory>, Box<dyn std::error::Error>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter(|m| matches_filter(m, filter))
+            .collect())
+    }
+}
@@ -1,50 +1,297 @@
+use crate::backend::{self, OllamaBackend, ReflectionBackend, TokenStream};
+use crate::config::Config;
+use crate::memory::{retrieve_top_k, MemoryStore, RetrievalWeights};
+use crate::tools::{self, ToolCall};
 use crate::types::{EgoThought, Memory, ThoughtMetrics};
 use anyhow::Result;
 use chrono::Utc;
-use reqwest::Client;
-use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
-#[derive(Clone)]
+/// Candidate pool is ranked and trimmed to this many memories before the
+/// reflection prompt is built, unless overridden via `with_retrieval`.
+const DEFAULT_RETRIEVAL_TOP_K: usize = 15;
+
+/// Fallback tool-step bound for engines built without a `Config` (e.g.
+/// `ReflectionEngine::new`/`with_backend`). `from_config` overrides this
+/// with `Config::max_tool_steps`.
+const DEFAULT_MAX_TOOL_STEPS: usize = 5;
+
+/// Handed back by `reflect_on_memories_stream`: the live token stream plus
+/// the metadata `build_thought` needs once the caller has the full text.
+pub struct StreamingReflection {
+    pub tokens: TokenStream,
+    pub context_hash: String,
+    pub model: String,
+}
+
 pub struct ReflectionEngine {
-    client: Client,
+    backend: Arc<dyn ReflectionBackend>,
     ollama_url: String,
-    model: String,
+    retrieval_top_k: usize,
+    retrieval_weights: RetrievalWeights,
+    max_tool_steps: usize,
+}
+
+impl Clone for ReflectionEngine {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            ollama_url: self.ollama_url.clone(),
+            retrieval_top_k: self.retrieval_top_k,
+            retrieval_weights: self.retrieval_weights,
+            max_tool_steps: self.max_tool_steps,
+        }
+    }
 }
 
 impl ReflectionEngine {
+    /// Convenience constructor for the default Ollama backend.
     pub fn new(ollama_url: String, model: String) -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30)) // 30 seconds timeout
-            .build()
-            .unwrap_or_else(|_| Client::new());
+        Self {
+            backend: Arc::new(OllamaBackend::new(ollama_url.clone(), model)),
+            ollama_url,
+            retrieval_top_k: DEFAULT_RETRIEVAL_TOP_K,
+            retrieval_weights: RetrievalWeights::default(),
+            max_tool_steps: DEFAULT_MAX_TOOL_STEPS,
+        }
+    }
+
+    /// Build whichever backend `config.provider` selects. For the Ollama
+    /// provider this probes `/api/tags` to pick the first installed model
+    /// from `config.models`, so it's async.
+    pub async fn from_config(config: &Config) -> Self {
+        Self {
+            backend: Arc::from(backend::from_config(config).await),
+            ollama_url: config.ollama_url.clone(),
+            retrieval_top_k: DEFAULT_RETRIEVAL_TOP_K,
+            retrieval_weights: RetrievalWeights::default(),
+            max_tool_steps: config.max_tool_steps,
+        }
+    }
 
+    pub fn with_backend(backend: Arc<dyn ReflectionBackend>, ollama_url: String) -> Self {
         Self {
-            client,
+            backend,
             ollama_url,
-            model,
+            retrieval_top_k: DEFAULT_RETRIEVAL_TOP_K,
+            retrieval_weights: RetrievalWeights::default(),
+            max_tool_steps: DEFAULT_MAX_TOOL_STEPS,
         }
     }
 
+    /// Override the retrieval top-k and component weights used to rank
+    /// candidate memories before reflection.
+    pub fn with_retrieval(mut self, top_k: usize, weights: RetrievalWeights) -> Self {
+        self.retrieval_top_k = top_k;
+        self.retrieval_weights = weights;
+        self
+    }
+
+    /// Rate how poignant/significant a memory is on a 1-10 scale via the
+    /// backend, for caching as the importance component of retrieval.
+    pub async fn rate_poignancy(&self, content: &str) -> Result<u8> {
+        let prompt = format!(
+            "Rate how poignant/significant this memory is, 1=mundane, 10=pivotal. \
+             Respond with only the integer, no other text.\n\nMEMORY: {}",
+            content
+        );
+        let response = self.generate(&prompt).await?;
+        let digits: String = response.chars().filter(|c| c.is_ascii_digit()).collect();
+        let rating: u8 = digits.parse().unwrap_or(5);
+        Ok(rating.clamp(1, 10))
+    }
+
     pub async fn reflect_on_memories(
         &self,
         memories: &[&Memory],
         user_query: Option<&str>,
     ) -> Result<EgoThought> {
-        // Use single-call approach for better performance
-        self.reflect_on_memories_single_call(memories, user_query)
+        self.reflect_on_memories_with_focus(memories, user_query, None)
             .await
     }
 
+    pub async fn reflect_on_memories_with_focus(
+        &self,
+        memories: &[&Memory],
+        user_query: Option<&str>,
+        focus_embedding: Option<&[f32]>,
+    ) -> Result<EgoThought> {
+        // Rank by recency/importance/relevance and keep only the top-k
+        // before the prompt is built, so the prompt stays grounded as the
+        // candidate pool grows.
+        let ranked = retrieve_top_k(
+            memories,
+            focus_embedding,
+            self.retrieval_top_k,
+            self.retrieval_weights,
+        );
+        self.reflect_on_memories_single_call(&ranked, user_query)
+            .await
+    }
+
+    /// Like `reflect_on_memories_with_focus`, but lets the model call back
+    /// into `memory_store` for extra context (searching memories, fetching
+    /// a consolidated experience, flagging ids for consolidation) before
+    /// committing to a final thought, bounded by `self.max_tool_steps` turns.
+    pub async fn reflect_on_memories_with_tools(
+        &self,
+        memories: &[&Memory],
+        user_query: Option<&str>,
+        focus_embedding: Option<&[f32]>,
+        memory_store: &Arc<RwLock<MemoryStore>>,
+    ) -> Result<EgoThought> {
+        let ranked = retrieve_top_k(
+            memories,
+            focus_embedding,
+            self.retrieval_top_k,
+            self.retrieval_weights,
+        );
+        self.reflect_with_tool_loop(&ranked, user_query, memory_store)
+            .await
+    }
+
+    async fn reflect_with_tool_loop(
+        &self,
+        memories: &[&Memory],
+        user_query: Option<&str>,
+        memory_store: &Arc<RwLock<MemoryStore>>,
+    ) -> Result<EgoThought> {
+        let mut transcript = String::new();
+        let mut consolidate_ids: Vec<String> = Vec::new();
+        let mut seen_calls: HashSet<String> = HashSet::new();
+
+        for step in 0..self.max_tool_steps {
+            let force_final = step == self.max_tool_steps - 1;
+            let prompt =
+                self.create_tool_aware_prompt(memories, user_query, &transcript, force_final);
+            let response = self.generate(&prompt).await?;
+            let json_str = extract_json_object(&response)?;
+
+            if !force_final {
+                if let Some(calls) = tools::parse_calls(json_str) {
+                    // Dedup on each call's own JSON form so a model that
+                    // repeats itself gets nudged to finalize instead of
+                    // burning its remaining steps on the same lookup.
+                    let fresh_calls: Vec<ToolCall> = calls
+                        .into_iter()
+                        .filter(|call| {
+                            let call_key = serde_json::to_string(call).unwrap_or_default();
+                            seen_calls.insert(call_key)
+                        })
+                        .collect();
+
+                    if fresh_calls.is_empty() {
+                        transcript.push_str(
+                            "\nYou already made that exact tool call; finalize your answer instead.\n",
+                        );
+                        continue;
+                    }
+
+                    for call in &fresh_calls {
+                        if let ToolCall::Consolidate { ids } = call {
+                            consolidate_ids.extend(ids.iter().cloned());
+                        }
+                    }
+
+                    // Independent calls in one step don't depend on each
+                    // other's results, so run them concurrently instead of
+                    // round-tripping the lock one at a time.
+                    let results: Vec<String> =
+                        futures::future::join_all(
+                            fresh_calls.iter().map(|call| tools::execute(call, memory_store)),
+                        )
+                        .await;
+
+                    for (call, result) in fresh_calls.iter().zip(results) {
+                        transcript.push_str(&format!(
+                            "\nTOOL CALL: {}\nRESULT: {}\n",
+                            tools::describe(call),
+                            result
+                        ));
+                    }
+                    continue;
+                }
+            }
+
+            let thought_data = self.parse_reflection_response(&response)?;
+            let mut consolidate = thought_data.consolidate;
+            for id in consolidate_ids {
+                if !consolidate.contains(&id) {
+                    consolidate.push(id);
+                }
+            }
+            consolidate.truncate(5);
+
+            return Ok(EgoThought {
+                id: Uuid::new_v4().to_string(),
+                title: thought_data.title,
+                thought: thought_data.thought,
+                metrics: thought_data.metrics,
+                consolidate,
+                generated_at: Utc::now(),
+                context_hash: self.generate_context_hash_from_memories(memories),
+                model: self.model().to_string(),
+            });
+        }
+
+        unreachable!("the forced-final step above always returns before the loop ends")
+    }
+
     async fn reflect_on_memories_single_call(
         &self,
         memories: &[&Memory],
         user_query: Option<&str>,
     ) -> Result<EgoThought> {
         let prompt = self.create_single_reflection_prompt(memories, user_query);
-        let response = self.call_ollama(&prompt).await?;
+        let response = self.generate(&prompt).await?;
+        let context_hash = self.generate_context_hash_from_memories(memories);
+        self.build_thought(&response, context_hash, self.model().to_string())
+    }
 
-        let thought_data = self.parse_reflection_response(&response)?;
+    /// Start a streaming reflection: ranks `memories` the same way as
+    /// `reflect_on_memories_with_focus`, then hands back the backend's raw
+    /// token stream alongside the metadata needed to assemble the final
+    /// `EgoThought` once the caller has accumulated the full response (see
+    /// `build_thought`). Used by the `/api/ego/reflect/stream` SSE route so
+    /// the client sees tokens as they're generated instead of waiting for
+    /// the whole reflection.
+    pub async fn reflect_on_memories_stream(
+        &self,
+        memories: &[&Memory],
+        user_query: Option<&str>,
+        focus_embedding: Option<&[f32]>,
+    ) -> Result<StreamingReflection> {
+        let ranked = retrieve_top_k(
+            memories,
+            focus_embedding,
+            self.retrieval_top_k,
+            self.retrieval_weights,
+        );
+        let prompt = self.create_single_reflection_prompt(&ranked, user_query);
+        let context_hash = self.generate_context_hash_from_memories(&ranked);
+        let tokens = self.backend.generate_stream(&prompt).await?;
+
+        Ok(StreamingReflection {
+            tokens,
+            context_hash,
+            model: self.model().to_string(),
+        })
+    }
+
+    /// Parse a (possibly streamed-then-reassembled) model response into an
+    /// `EgoThought`, using the `context_hash`/`model` computed up front by
+    /// `reflect_on_memories_stream` so the caller doesn't need to keep the
+    /// borrowed memory slice alive until streaming completes.
+    pub fn build_thought(
+        &self,
+        response: &str,
+        context_hash: String,
+        model: String,
+    ) -> Result<EgoThought> {
+        let thought_data = self.parse_reflection_response(response)?;
 
         Ok(EgoThought {
             id: Uuid::new_v4().to_string(),
@@ -53,8 +300,8 @@ impl ReflectionEngine {
             metrics: thought_data.metrics,
             consolidate: thought_data.consolidate,
             generated_at: Utc::now(),
-            context_hash: self.generate_context_hash_from_memories(memories),
-            model: self.model.clone(),
+            context_hash,
+            model,
         })
     }
 
@@ -192,54 +439,51 @@ MEMORIES:
         )
     }
 
-    pub async fn call_ollama(&self, prompt: &str) -> Result<String> {
-        let request_body = json!({
-            "model": self.model,
-            "prompt": prompt,
-            "options": {
-                "temperature": 0.2,
-                "top_p": 0.9,
-                "repeat_penalty": 1.1
-            },
-            "stream": true
-        });
-
-        let response = self
-            .client
-            .post(&format!("{}/api/generate", self.ollama_url))
-            .json(&request_body)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("Ollama API error: {}", response.status());
+    /// `create_single_reflection_prompt` plus a tool catalog and the
+    /// running transcript of calls made so far. On the forced-final step
+    /// the tool catalog is dropped so the model can't keep stalling.
+    fn create_tool_aware_prompt(
+        &self,
+        memories: &[&Memory],
+        user_query: Option<&str>,
+        transcript: &str,
+        force_final: bool,
+    ) -> String {
+        let base = self.create_single_reflection_prompt(memories, user_query);
+
+        if force_final {
+            return format!(
+                "{}\n\nTOOL RESULTS SO FAR:{}\n\nRespond now with ONLY the final JSON reflection \
+                 described above. Do not request another tool.",
+                base, transcript
+            );
         }
 
-        let text = response.text().await?;
-        let lines: Vec<&str> = text.trim().split('\n').collect();
-
-        let mut result = String::new();
-        for line in lines {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-                if let Some(response_text) = json.get("response").and_then(|v| v.as_str()) {
-                    result.push_str(response_text);
-                }
-            }
-        }
+        format!(
+            "{}\n\nBefore answering, you may call tools to gather more context. Available tools:\n\
+             {}\n\n\
+             Respond with ONLY a single tool-call JSON object, e.g. \
+             {{\"tool\": \"search_memories\", \"query\": \"...\", \"modality\": \"vision|speech|text|concept\"}}, \
+             or a JSON array of tool-call objects to run several independent lookups in one turn, \
+             instead of the final reflection.\n\
+             Once you have enough context, respond with the final reflection JSON instead.\n\
+             TOOL RESULTS SO FAR:{}",
+            base,
+            tools::schemas(),
+            transcript
+        )
+    }
 
-        Ok(result.trim().to_string())
+    /// Send `prompt` through the configured backend. Replaces the old
+    /// Ollama-only `call_ollama`; kept under the same name's call sites via
+    /// this generic entry point so `consolidate`/`status` work unchanged
+    /// across providers.
+    pub async fn generate(&self, prompt: &str) -> Result<String> {
+        self.backend.generate(prompt).await
     }
 
     fn parse_reflection_response(&self, response: &str) -> Result<ReflectionResponse> {
-        // Extract JSON from response
-        let start = response.find('{');
-        let end = response.rfind('}');
-
-        let json_str = match (start, end) {
-            (Some(start), Some(end)) => &response[start..=end],
-            _ => anyhow::bail!("No JSON found in response"),
-        };
-
+        let json_str = extract_json_object(response)?;
         let parsed: ReflectionResponse = serde_json::from_str(json_str)?;
 
         // Validate the response
@@ -268,32 +512,22 @@ MEMORIES:
             format!("{:?}", memory.modality).hash(&mut hasher);
         }
 
-        self.model.hash(&mut hasher);
+        self.model().hash(&mut hasher);
         memories.len().hash(&mut hasher);
 
         format!("{:x}", hasher.finish())
     }
 
     pub async fn check_ollama_health(&self) -> Result<bool> {
-        let response = self
-            .client
-            .get(&format!("{}/api/tags", self.ollama_url))
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await;
-
-        match response {
-            Ok(resp) => Ok(resp.status().is_success()),
-            Err(_) => Ok(false),
-        }
+        self.backend.health().await
     }
 
     pub fn ollama_url(&self) -> &String {
         &self.ollama_url
     }
 
-    pub fn model(&self) -> &String {
-        &self.model
+    pub fn model(&self) -> &str {
+        self.backend.model()
     }
 }
 
@@ -304,3 +538,26 @@ struct ReflectionResponse {
     metrics: ThoughtMetrics,
     consolidate: Vec<String>,
 }
+
+/// Find the outermost JSON value in a model response, ignoring any prose
+/// the model wrapped it in. The response may be a single `{...}` tool-call
+/// or reflection object, or a `[...]` array of tool-call objects - whichever
+/// bracket opens first in the response wins, and its matching close bracket
+/// is the last one of that kind. Shared by final-answer and tool-call
+/// parsing.
+fn extract_json_object(response: &str) -> Result<&str> {
+    let brace_start = response.find('{');
+    let bracket_start = response.find('[');
+
+    let (start, close) = match (brace_start, bracket_start) {
+        (Some(b), Some(k)) if k < b => (k, ']'),
+        (Some(b), _) => (b, '}'),
+        (None, Some(k)) => (k, ']'),
+        (None, None) => anyhow::bail!("No JSON found in response"),
+    };
+
+    match response.rfind(close) {
+        Some(end) if end > start => Ok(&response[start..=end]),
+        _ => anyhow::bail!("No JSON found in response"),
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::Config;
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Backing store for the gauges a periodic sampler reports: STM/LTM size
+/// and Ollama up/down don't change per-request the way the counters and
+/// histograms below do, so they're sampled on a timer instead of recorded
+/// inline in each handler.
+#[derive(Clone, Default)]
+pub struct GaugeState {
+    pub stm_size: Arc<AtomicU64>,
+    pub ltm_size: Arc<AtomicU64>,
+    pub ollama_up: Arc<AtomicBool>,
+}
+
+/// Metrics recorded across the reflect/consolidate handlers. Every
+/// instrument is built from `opentelemetry::global::meter`, which is a
+/// documented no-op until a `MeterProvider` is installed — so leaving
+/// `config.otel_exporter_endpoint` unset makes every `.add`/`.record` call
+/// below free instead of needing an enabled/disabled check at each site.
+#[derive(Clone)]
+pub struct Telemetry {
+    pub reflections_served: Counter<u64>,
+    pub fallback_thoughts: Counter<u64>,
+    pub consolidations_run: Counter<u64>,
+    pub experiences_created: Counter<u64>,
+    pub reflect_latency_ms: Histogram<f64>,
+    pub consolidate_latency_ms: Histogram<f64>,
+    pub gauges: GaugeState,
+}
+
+fn resource() -> Resource {
+    Resource::new(vec![KeyValue::new("service.name", "ego-rs")])
+}
+
+impl Telemetry {
+    /// Install the OTLP metrics pipeline when `config.otel_exporter_endpoint`
+    /// is set, then build every instrument against whichever `MeterProvider`
+    /// (installed or default no-op) is current.
+    pub fn init(config: &Config) -> Self {
+        if let Some(endpoint) = &config.otel_exporter_endpoint {
+            let pipeline = opentelemetry_otlp::new_pipeline()
+                .metrics(opentelemetry_sdk::runtime::Tokio)
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_resource(resource())
+                .build();
+
+            match pipeline {
+                Ok(provider) => global::set_meter_provider(provider),
+                Err(e) => tracing::error!("Failed to start OTLP metrics pipeline: {}", e),
+            }
+        }
+
+        let meter = global::meter("ego-rs");
+        Self {
+            reflections_served: meter
+                .u64_counter("ego.reflections_served")
+                .with_description("Reflections served by /api/ego/reflect")
+                .init(),
+            fallback_thoughts: meter
+                .u64_counter("ego.fallback_thoughts")
+                .with_description(
+                    "Times generate_fallback_thought ran instead of a model response",
+                )
+                .init(),
+            consolidations_run: meter
+                .u64_counter("ego.consolidations_run")
+                .with_description("STM-to-LTM consolidations run")
+                .init(),
+            experiences_created: meter
+                .u64_counter("ego.experiences_created")
+                .with_description("LTM experiences created by consolidation")
+                .init(),
+            reflect_latency_ms: meter
+                .f64_histogram("ego.reflect_on_memories.duration_ms")
+                .with_description("reflect_on_memories_with_tools latency")
+                .init(),
+            consolidate_latency_ms: meter
+                .f64_histogram("ego.consolidate_thoughts.duration_ms")
+                .with_description("consolidate_thoughts latency")
+                .init(),
+            gauges: GaugeState::default(),
+        }
+    }
+
+    /// Spawn the periodic sampler that reports `gauges` to OTel observable
+    /// gauges: STM/LTM size read off `memory_store`, Ollama health via
+    /// `check_ollama_health`, sampled every 30s.
+    pub fn spawn_gauge_reporter(
+        &self,
+        memory_store: Arc<tokio::sync::RwLock<crate::memory::MemoryStore>>,
+        reflection_engine: Arc<crate::reflection::ReflectionEngine>,
+    ) {
+        let meter = global::meter("ego-rs");
+
+        let stm_gauge = self.gauges.stm_size.clone();
+        let _stm_observable = meter
+            .u64_observable_gauge("ego.stm_size")
+            .with_description("Number of STM memories currently held")
+            .with_callback(move |observer| observer.observe(stm_gauge.load(Ordering::Relaxed), &[]))
+            .init();
+
+        let ltm_gauge = self.gauges.ltm_size.clone();
+        let _ltm_observable = meter
+            .u64_observable_gauge("ego.ltm_size")
+            .with_description("Number of LTM experiences currently held")
+            .with_callback(move |observer| observer.observe(ltm_gauge.load(Ordering::Relaxed), &[]))
+            .init();
+
+        let ollama_gauge = self.gauges.ollama_up.clone();
+        let _ollama_observable = meter
+            .u64_observable_gauge("ego.ollama_up")
+            .with_description("1 if the last check_ollama_health call succeeded, 0 otherwise")
+            .with_callback(move |observer| {
+                observer.observe(ollama_gauge.load(Ordering::Relaxed) as u64, &[])
+            })
+            .init();
+
+        let gauges = self.gauges.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(30));
+            loop {
+                interval.tick().await;
+
+                let (stm_size, ltm_size) = {
+                    let store = memory_store.read().await;
+                    (store.get_all_memories().len(), store.get_experiences().len())
+                };
+                gauges.stm_size.store(stm_size as u64, Ordering::Relaxed);
+                gauges.ltm_size.store(ltm_size as u64, Ordering::Relaxed);
+
+                let up = reflection_engine
+                    .check_ollama_health()
+                    .await
+                    .unwrap_or(false);
+                gauges.ollama_up.store(up, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+/// Install the `tracing` subscriber: plain formatted logs, plus an OTLP
+/// trace exporter layer when `config.otel_exporter_endpoint` is set so the
+/// spans `handlers::reflect`/`handlers::consolidate_stm_to_ltm` emit show up
+/// as real traces instead of just log lines.
+pub fn init_subscriber(config: &Config) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+
+    match &config.otel_exporter_endpoint {
+        Some(endpoint) => {
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(opentelemetry_sdk::trace::config().with_resource(resource()))
+                .install_batch(opentelemetry_sdk::runtime::Tokio);
+
+            match tracer {
+                Ok(tracer) => registry.with(tracing_opentelemetry::layer().with_tracer(tracer)).init(),
+                Err(e) => {
+                    eprintln!("Failed to start OTLP trace pipeline: {}", e);
+                    registry.init();
+                }
+            }
+        }
+        None => registry.init(),
+    }
+}
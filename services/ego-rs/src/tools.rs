@@ -0,0 +1,219 @@
+//! Tools the reflection engine can call mid-reflection to pull extra
+//! context from the memory store before committing to a final thought,
+//! instead of relying solely on the candidate pool handed to it up front.
+
+use crate::memory::MemoryStore;
+use crate::types::{Memory, Modality};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// A single tool invocation, parsed out of the model's response JSON via
+/// the `tool` tag. Kept in sync with the schema advertised in
+/// `ReflectionEngine::create_tool_aware_prompt`.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+pub enum ToolCall {
+    /// Keyword search over stored memories, optionally narrowed by modality.
+    SearchMemories {
+        query: String,
+        modality: Option<String>,
+    },
+    /// Look up a consolidated LTM experience by id.
+    FetchExperience { id: String },
+    /// Flag memory ids as consolidation candidates without ending the loop.
+    Consolidate { ids: Vec<String> },
+    /// Look up a single stored memory by its id (the embedding id assigned
+    /// when it was first captured).
+    LookupMemory { embedding_id: String },
+    /// Average affect (valence/arousal) over memories from the last
+    /// `window_minutes`, so the model can ground mood claims in numbers
+    /// instead of guessing from a handful of recent memories.
+    AggregateAffect { window_minutes: u64 },
+    /// List memories carrying a given facet key, e.g. `vision.object`.
+    SearchFacets { key: String },
+}
+
+/// Parse one model turn's tool-call JSON, accepting either a single call
+/// object or a JSON array of them so the model can request several
+/// independent lookups in one step instead of round-tripping one at a time.
+pub fn parse_calls(json_str: &str) -> Option<Vec<ToolCall>> {
+    if let Ok(calls) = serde_json::from_str::<Vec<ToolCall>>(json_str) {
+        return Some(calls);
+    }
+    serde_json::from_str::<ToolCall>(json_str).ok().map(|c| vec![c])
+}
+
+/// JSON schema for every tool in the registry, sent to the model so it
+/// knows the exact call shape to emit. Kept alongside `ToolCall` so the two
+/// can't drift out of sync.
+pub fn schemas() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "tool": "search_memories",
+            "description": "Keyword search over stored memories, optionally narrowed by modality.",
+            "arguments": {"query": "string", "modality": "vision|speech|text|concept (optional)"}
+        },
+        {
+            "tool": "fetch_experience",
+            "description": "Look up a consolidated LTM experience by id.",
+            "arguments": {"id": "string"}
+        },
+        {
+            "tool": "consolidate",
+            "description": "Flag memory ids as consolidation candidates without ending the loop.",
+            "arguments": {"ids": ["string"]}
+        },
+        {
+            "tool": "lookup_memory",
+            "description": "Look up a single stored memory by its embedding id.",
+            "arguments": {"embedding_id": "string"}
+        },
+        {
+            "tool": "aggregate_affect",
+            "description": "Average valence/arousal over memories from the last N minutes.",
+            "arguments": {"window_minutes": "integer"}
+        },
+        {
+            "tool": "search_facets",
+            "description": "List memories carrying a given facet key.",
+            "arguments": {"key": "string"}
+        }
+    ])
+}
+
+/// Render `call` back to a short human-readable line for the transcript
+/// the model sees on its next turn.
+pub fn describe(call: &ToolCall) -> String {
+    match call {
+        ToolCall::SearchMemories { query, modality } => match modality {
+            Some(modality) => format!("search_memories(query=\"{}\", modality={})", query, modality),
+            None => format!("search_memories(query=\"{}\")", query),
+        },
+        ToolCall::FetchExperience { id } => format!("fetch_experience(id=\"{}\")", id),
+        ToolCall::Consolidate { ids } => format!("consolidate(ids={:?})", ids),
+        ToolCall::LookupMemory { embedding_id } => {
+            format!("lookup_memory(embedding_id=\"{}\")", embedding_id)
+        }
+        ToolCall::AggregateAffect { window_minutes } => {
+            format!("aggregate_affect(window_minutes={})", window_minutes)
+        }
+        ToolCall::SearchFacets { key } => format!("search_facets(key=\"{}\")", key),
+    }
+}
+
+/// Run `call` against `memory_store` and render its result as plain text
+/// to append to the reflection transcript.
+pub async fn execute(call: &ToolCall, memory_store: &Arc<RwLock<MemoryStore>>) -> String {
+    match call {
+        ToolCall::SearchMemories { query, modality } => {
+            let store = memory_store.read().await;
+            let wanted = modality.as_deref().and_then(parse_modality);
+            let query_lower = query.to_lowercase();
+            let matches: Vec<&Memory> = store
+                .get_all_memories()
+                .into_iter()
+                .filter(|m| match &wanted {
+                    Some(modality) => modality_eq(&m.modality, modality),
+                    None => true,
+                })
+                .filter(|m| m.content.to_lowercase().contains(&query_lower))
+                .take(5)
+                .collect();
+
+            if matches.is_empty() {
+                format!("No memories matched \"{}\".", query)
+            } else {
+                matches
+                    .iter()
+                    .map(|m| format!("- [{}] {}", m.id, m.content))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        ToolCall::FetchExperience { id } => {
+            let store = memory_store.read().await;
+            match store.get_experience(id) {
+                Some(experience) => format!("{}: {}", experience.id, experience.summary),
+                None => format!("No experience found with id {}.", id),
+            }
+        }
+        ToolCall::Consolidate { ids } => {
+            format!("Noted {} memory id(s) for consolidation.", ids.len())
+        }
+        ToolCall::LookupMemory { embedding_id } => {
+            let store = memory_store.read().await;
+            match store.get_memory(embedding_id) {
+                Some(memory) => format!("[{}] {}", memory.id, memory.content),
+                None => format!("No memory found with id {}.", embedding_id),
+            }
+        }
+        ToolCall::AggregateAffect { window_minutes } => {
+            let store = memory_store.read().await;
+            let recent = store.get_recent_memories(usize::MAX, *window_minutes);
+            if recent.is_empty() {
+                return format!("No memories in the last {} minutes.", window_minutes);
+            }
+            let (valence_sum, valence_n) = sum_facet(&recent, "affect.valence");
+            let (arousal_sum, arousal_n) = sum_facet(&recent, "affect.arousal");
+            format!(
+                "Over the last {} minutes ({} memories): avg valence={}, avg arousal={}.",
+                window_minutes,
+                recent.len(),
+                average(valence_sum, valence_n),
+                average(arousal_sum, arousal_n),
+            )
+        }
+        ToolCall::SearchFacets { key } => {
+            let store = memory_store.read().await;
+            let matches: Vec<&Memory> = store
+                .get_all_memories()
+                .into_iter()
+                .filter(|m| m.facets.contains_key(key))
+                .take(5)
+                .collect();
+
+            if matches.is_empty() {
+                format!("No memories carry the facet \"{}\".", key)
+            } else {
+                matches
+                    .iter()
+                    .map(|m| format!("- [{}] {} = {}", m.id, key, m.facets[key]))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+    }
+}
+
+/// Sum of a numeric facet across `memories`, alongside how many carried it.
+fn sum_facet(memories: &[&Memory], key: &str) -> (f64, usize) {
+    memories
+        .iter()
+        .filter_map(|m| m.facets.get(key).and_then(|v| v.as_f64()))
+        .fold((0.0, 0), |(sum, n), v| (sum + v, n + 1))
+}
+
+fn average(sum: f64, n: usize) -> String {
+    if n == 0 {
+        "n/a".to_string()
+    } else {
+        format!("{:.2}", sum / n as f64)
+    }
+}
+
+fn parse_modality(s: &str) -> Option<Modality> {
+    match s.to_lowercase().as_str() {
+        "vision" => Some(Modality::Vision),
+        "speech" => Some(Modality::Speech),
+        "text" => Some(Modality::Text),
+        "concept" => Some(Modality::Concept),
+        _ => None,
+    }
+}
+
+fn modality_eq(a: &Modality, b: &Modality) -> bool {
+    // Modality doesn't implement PartialEq; compare via Debug the same way
+    // `reflection::generate_context_hash_from_memories` already does.
+    format!("{:?}", a) == format!("{:?}", b)
+}
@@ -11,6 +11,23 @@ pub struct Memory {
     pub content: String,
     pub facets: HashMap<String, serde_json::Value>,
     pub tags: Vec<String>,
+    /// Where in the original transcript/image/document this memory's
+    /// content came from, if it's locatable. Absent for legacy records and
+    /// for memories with no backing source (e.g. a live reflection thought).
+    #[serde(default)]
+    pub source_ref: Option<SourceRef>,
+}
+
+/// A pointer back to the exact span of a source that produced a memory —
+/// the file path/URI, the byte or character range within it, and which
+/// chunk this was if the source was split into several. Lets a UI jump from
+/// a retrieved memory to the original transcript, image region, or document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRef {
+    pub uri: String,
+    pub start: usize,
+    pub end: usize,
+    pub chunk_index: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +80,10 @@ pub struct Experience {
     pub importance: f32,     // 0.0-1.0
     pub context_hash: String,
     pub tags: Vec<String>,
+    /// Embedding of `summary`, for semantic search over LTM. Absent on
+    /// experiences created before embedding support was added.
+    #[serde(default)]
+    pub embedding: Vec<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +112,74 @@ pub struct MemoryQuery {
     pub limit: Option<usize>,
     pub modality: Option<Modality>,
     pub since: Option<DateTime<Utc>>,
+    /// Free-text query for semantic search: when present, results are the
+    /// nearest memories/experiences by embedding cosine similarity to `q`
+    /// instead of plain modality/recency filtering.
+    pub q: Option<String>,
+}
+
+/// Query params for the `/memories/poll` long-poll endpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PollQuery {
+    /// Causality token: only memories/experiences created strictly after
+    /// this epoch-millis timestamp are returned. Pass the previous
+    /// response's `cursor` here so concurrent clients don't miss or
+    /// duplicate events.
+    #[serde(default)]
+    pub since: i64,
+    /// How long to hold the request open waiting for new data, in
+    /// milliseconds. Capped server-side; defaults if unset.
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+/// Delta returned by `/memories/poll`: everything created after the
+/// request's `since` cursor, plus the cursor to pass on the next call.
+#[derive(Debug, Clone, Serialize)]
+pub struct PollResult {
+    pub memories: Vec<Memory>,
+    pub experiences: Vec<Experience>,
+    pub cursor: i64,
+}
+
+/// Request body for `/memories/batch`: the reads and inserts to apply
+/// under a single `MemoryStore` lock acquisition instead of one lock per
+/// memory. Each insert is still appended to the backend on its own as it
+/// is applied; there is no single save for the whole batch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchRequest {
+    /// Modality/since/limit reads, run against the store as it stands
+    /// after `inserts` have been applied. Semantic (`q`) reads aren't
+    /// supported here: embedding a query is async, and awaiting one while
+    /// holding the store lock would reintroduce the per-op cost this
+    /// endpoint exists to avoid.
+    #[serde(default)]
+    pub reads: Vec<MemoryQuery>,
+    #[serde(default)]
+    pub inserts: Vec<Memory>,
+}
+
+/// One result slice per entry in `BatchRequest::reads`, plus how many
+/// `inserts` were applied.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+    pub reads: Vec<Vec<Memory>>,
+    pub inserted: usize,
+}
+
+/// Request body for `/api/ego/rotate-key`: re-seal the STM JSONL file under
+/// a new key. Both keys are 64 hex character (32-byte) symmetric keys, same
+/// format as `Config::encryption_key`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RotateKeyRequest {
+    pub old_key: String,
+    pub new_key: String,
+}
+
+/// How many STM records `/api/ego/rotate-key` re-sealed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RotateKeyResult {
+    pub rotated: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,125 @@
+//! Layered configuration for the Embeddings service: `Default`, then an
+//! optional `config.toml`, then `LJ_*` environment overrides, mirroring
+//! ego-rs's and sentience-rs's config loaders.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_bind_addr")]
+    pub bind_addr: String,
+    /// Which `EmbeddingProvider` to build: "ollama" (default), "openai", or
+    /// "ml_service" for the self-hosted dimension-reduction/ML service.
+    #[serde(default = "default_embedding_provider")]
+    pub embedding_provider: String,
+    /// Embedding model name passed to the provider.
+    #[serde(default = "default_embedding_model")]
+    pub embedding_model: String,
+    /// Base URL for the `ollama` provider.
+    #[serde(default = "default_ollama_url")]
+    pub ollama_url: String,
+    /// Base URL for the `openai` provider.
+    #[serde(default)]
+    pub api_base: Option<String>,
+    /// API key for the `openai` provider.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Base URL for the `ml_service` provider.
+    #[serde(default = "default_ml_service_url")]
+    pub ml_service_url: String,
+    /// Max attempts (including the first) for retried ML-service calls
+    /// like `reduce-dimensions`, before giving up and surfacing the error.
+    #[serde(default = "default_ml_service_max_attempts")]
+    pub ml_service_max_attempts: u32,
+}
+
+fn default_bind_addr() -> String {
+    "0.0.0.0:8085".to_string()
+}
+
+fn default_embedding_provider() -> String {
+    "ollama".to_string()
+}
+
+fn default_embedding_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_ollama_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_ml_service_url() -> String {
+    "http://localhost:8081".to_string()
+}
+
+fn default_ml_service_max_attempts() -> u32 {
+    3
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_addr: default_bind_addr(),
+            embedding_provider: default_embedding_provider(),
+            embedding_model: default_embedding_model(),
+            ollama_url: default_ollama_url(),
+            api_base: None,
+            api_key: None,
+            ml_service_url: default_ml_service_url(),
+            ml_service_max_attempts: default_ml_service_max_attempts(),
+        }
+    }
+}
+
+impl Config {
+    /// Layer configuration lowest-to-highest precedence: `Default`, an
+    /// optional `config.toml` in the working directory, then `LJ_*`
+    /// environment variables.
+    pub fn load() -> Self {
+        let mut config = Self::from_toml_file("config.toml").unwrap_or_default();
+        config.apply_env_overrides();
+        config
+    }
+
+    fn from_toml_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        match toml::from_str(&contents) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("Failed to parse {}: {}; using defaults", path, e);
+                None
+            }
+        }
+    }
+
+    fn apply_env_overrides(&mut self) {
+        if let Ok(v) = std::env::var("LJ_BIND_ADDR") {
+            self.bind_addr = v;
+        }
+        if let Ok(v) = std::env::var("LJ_EMBEDDING_PROVIDER") {
+            self.embedding_provider = v;
+        }
+        if let Ok(v) = std::env::var("LJ_EMBEDDING_MODEL") {
+            self.embedding_model = v;
+        }
+        if let Ok(v) = std::env::var("LJ_OLLAMA_URL") {
+            self.ollama_url = v;
+        }
+        if let Ok(v) = std::env::var("LJ_API_BASE") {
+            self.api_base = Some(v);
+        }
+        if let Ok(v) = std::env::var("LJ_API_KEY") {
+            self.api_key = Some(v);
+        }
+        if let Ok(v) = std::env::var("LJ_ML_SERVICE_URL") {
+            self.ml_service_url = v;
+        }
+        if let Ok(v) = std::env::var("LJ_ML_SERVICE_MAX_ATTEMPTS") {
+            match v.parse() {
+                Ok(n) => self.ml_service_max_attempts = n,
+                Err(e) => eprintln!("Ignoring invalid LJ_ML_SERVICE_MAX_ATTEMPTS={:?}: {}", v, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Turns raw text into vectors so `/embed` can ingest content without every
+//! caller running its own model, mirroring the provider-abstraction ego-rs
+//! uses for reflection and memory embeddings.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Batch text -> vector. Implementations are free to fan a batch out into
+/// one request per input (Ollama has no batch endpoint) or send it as a
+/// single call (OpenAI, the self-hosted ML service).
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+fn http_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// Ollama's `/api/embeddings` endpoint. One request per input since the
+/// endpoint only accepts a single `prompt`.
+pub struct OllamaEmbeddingProvider {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(base_url: String, model: String) -> Self {
+        Self {
+            client: http_client(),
+            base_url,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(inputs.len());
+        for text in inputs {
+            let response = self
+                .client
+                .post(format!("{}/api/embeddings", self.base_url))
+                .json(&json!({ "model": self.model, "prompt": text }))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                anyhow::bail!("Ollama embeddings API error: {}", response.status());
+            }
+
+            let body: serde_json::Value = response.json().await?;
+            let embedding = body["embedding"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Ollama embeddings response missing 'embedding'"))?
+                .iter()
+                .filter_map(|v| v.as_f64())
+                .map(|v| v as f32)
+                .collect();
+
+            embeddings.push(embedding);
+        }
+        Ok(embeddings)
+    }
+}
+
+/// OpenAI's `/v1/embeddings` endpoint (`text-embedding-*` models), which
+/// accepts the whole batch as `input` in a single call.
+pub struct OpenAiEmbeddingProvider {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        Self {
+            client: http_client(),
+            base_url,
+            api_key,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({ "model": self.model, "input": inputs }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("OpenAI embeddings API error: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let data = body["data"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("OpenAI embeddings response missing 'data'"))?;
+
+        data.iter()
+            .map(|entry| {
+                entry["embedding"]
+                    .as_array()
+                    .ok_or_else(|| anyhow::anyhow!("OpenAI embeddings entry missing 'embedding'"))
+                    .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+            })
+            .collect()
+    }
+}
+
+/// The self-hosted ML service already running on `:8081` for tasks like
+/// `reduce-dimensions`. Sends the whole batch as `texts` in one call.
+pub struct MlServiceEmbeddingProvider {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl MlServiceEmbeddingProvider {
+    pub fn new(base_url: String, model: String) -> Self {
+        Self {
+            client: http_client(),
+            base_url,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MlServiceEmbeddingProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(format!("{}/embed", self.base_url))
+            .json(&json!({ "texts": inputs, "model": self.model }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("ML service embed API error: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let embeddings = body["embeddings"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("ML service response missing 'embeddings'"))?;
+
+        embeddings
+            .iter()
+            .map(|entry| {
+                entry
+                    .as_array()
+                    .ok_or_else(|| anyhow::anyhow!("ML service embedding entry is not an array"))
+                    .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+            })
+            .collect()
+    }
+}
+
+/// Build an `EmbeddingProvider` from `config.embedding_provider`.
+pub fn from_config(config: &Config) -> Box<dyn EmbeddingProvider> {
+    match config.embedding_provider.as_str() {
+        "openai" => Box::new(OpenAiEmbeddingProvider::new(
+            config
+                .api_base
+                .clone()
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            config.api_key.clone().unwrap_or_default(),
+            config.embedding_model.clone(),
+        )),
+        "ml_service" => Box::new(MlServiceEmbeddingProvider::new(
+            config.ml_service_url.clone(),
+            config.embedding_model.clone(),
+        )),
+        _ => Box::new(OllamaEmbeddingProvider::new(
+            config.ollama_url.clone(),
+            config.embedding_model.clone(),
+        )),
+    }
+}
+
+/// Normalize `vector` to unit length in place. A no-op on a zero vector
+/// (left as-is rather than dividing by zero).
+pub fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
@@ -1,6 +1,14 @@
+mod config;
+mod embedding;
+mod retry;
+
+use config::Config;
+use embedding::EmbeddingProvider;
+use retry::{Retry, RetryStrategy};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use uuid::Uuid;
 use warp::Filter;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +19,21 @@ pub struct EmbeddingData {
     pub embedding: Vec<f32>,
     pub facets: HashMap<String, serde_json::Value>,
     pub confidence: f32,
+    /// Where this vector's content came from, if it's locatable — the file
+    /// path/URI plus the byte or character range within it. Absent for
+    /// legacy records and for content with no backing source.
+    #[serde(default)]
+    pub source_ref: Option<SourceRef>,
+}
+
+/// A pointer back to the exact span of a source that produced an embedding,
+/// mirroring ego-rs's `SourceRef` on `Memory`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRef {
+    pub uri: String,
+    pub start: usize,
+    pub end: usize,
+    pub chunk_index: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,12 +66,23 @@ impl EmbeddingStore {
 
 type EmbeddingStoreRef = Arc<RwLock<EmbeddingStore>>;
 
+#[derive(Debug, Deserialize)]
+struct EmbedRequest {
+    texts: Vec<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    facets: HashMap<String, serde_json::Value>,
+}
+
 #[tokio::main]
 async fn main() {
-    println!("Embeddings service starting on :8085");
+    let config = Config::load();
+    println!("Embeddings service starting on {}", config.bind_addr);
     println!("I am Embeddings service");
 
     let store: EmbeddingStoreRef = Arc::new(RwLock::new(EmbeddingStore::new()));
+    let embedding_provider: Arc<dyn EmbeddingProvider> = Arc::from(embedding::from_config(&config));
 
     let cors = warp::cors()
         .allow_any_origin()
@@ -97,23 +131,49 @@ async fn main() {
     let reduce_dimensions = warp::path("reduce-dimensions")
         .and(warp::post())
         .and(warp::body::json())
+        .and(with_ml_service_max_attempts(config.ml_service_max_attempts))
         .and_then(reduce_dimensions_handler);
 
+    let embed = warp::path("embed")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_store(store.clone()))
+        .and(with_embedding_provider(embedding_provider.clone()))
+        .and_then(embed_handler);
+
     let routes = ping
         .or(healthz)
         .or(add_embedding)
         .or(get_embeddings)
         .or(get_embeddings_by_source)
         .or(reduce_dimensions)
+        .or(embed)
         .with(cors);
 
-    warp::serve(routes).run(([0, 0, 0, 0], 8085)).await;
+    let addr: std::net::SocketAddr = config
+        .bind_addr
+        .parse()
+        .unwrap_or_else(|e| panic!("Invalid bind_addr {:?}: {}", config.bind_addr, e));
+    warp::serve(routes).run(addr).await;
 }
 
 fn with_store(store: EmbeddingStoreRef) -> impl Filter<Extract = (EmbeddingStoreRef,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || store.clone())
 }
 
+fn with_embedding_provider(
+    embedding_provider: Arc<dyn EmbeddingProvider>,
+) -> impl Filter<Extract = (Arc<dyn EmbeddingProvider>,), Error = std::convert::Infallible> + Clone
+{
+    warp::any().map(move || embedding_provider.clone())
+}
+
+fn with_ml_service_max_attempts(
+    max_attempts: u32,
+) -> impl Filter<Extract = (u32,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || max_attempts)
+}
+
 async fn add_embedding_handler(
     data: EmbeddingData,
     store: EmbeddingStoreRef,
@@ -152,33 +212,112 @@ async fn get_embeddings_by_source_handler(
 
 async fn reduce_dimensions_handler(
     data: serde_json::Value,
+    max_attempts: u32,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let embeddings = data["embeddings"]
         .as_array()
         .ok_or_else(|| warp::reject::custom(EmbeddingError::InvalidData))?;
-    
+
     let method = data["method"].as_str().unwrap_or("pca");
     let n_components = data["n_components"].as_u64().unwrap_or(3) as usize;
 
-    // Call ML service for dimension reduction
     let client = reqwest::Client::new();
-    let ml_response = client
+    let payload = serde_json::json!({
+        "embeddings": embeddings,
+        "method": method,
+        "n_components": n_components
+    });
+
+    let result = retry::with_retry(max_attempts, || {
+        let client = client.clone();
+        let payload = payload.clone();
+        async move { call_reduce_dimensions(&client, &payload).await }
+    })
+    .await
+    .map_err(|_| warp::reject::custom(EmbeddingError::MLServiceError))?;
+
+    Ok(warp::reply::json(&result))
+}
+
+/// One attempt at calling the `:8081` ML service's `reduce-dimensions`
+/// endpoint, classifying any failure as retryable or fatal for
+/// `retry::with_retry` to act on.
+async fn call_reduce_dimensions(
+    client: &reqwest::Client,
+    payload: &serde_json::Value,
+) -> Result<serde_json::Value, Retry> {
+    let response = client
         .post("http://localhost:8081/reduce-dimensions")
-        .json(&serde_json::json!({
-            "embeddings": embeddings,
-            "method": method,
-            "n_components": n_components
-        }))
+        .json(payload)
         .send()
         .await
-        .map_err(|_| warp::reject::custom(EmbeddingError::MLServiceError))?;
+        .map_err(|e| {
+            let strategy = retry::classify_transport_error(&e);
+            Retry {
+                error: e.into(),
+                strategy,
+            }
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error = anyhow::anyhow!("ML service returned {}", status);
+        return Err(match retry::classify_status(status) {
+            RetryStrategy::GiveUp => Retry::give_up(error),
+            RetryStrategy::RetryAfterRateLimit => Retry::retry_after_rate_limit(error),
+            RetryStrategy::Retry => Retry::retry(error),
+        });
+    }
 
-    let result: serde_json::Value = ml_response
-        .json()
+    response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| Retry::give_up(e.into()))
+}
+
+/// Turn raw `texts` into vectors via the configured `EmbeddingProvider`,
+/// normalize each to unit length, and store one `EmbeddingData` per input
+/// under a freshly generated id.
+async fn embed_handler(
+    req: EmbedRequest,
+    store: EmbeddingStoreRef,
+    provider: Arc<dyn EmbeddingProvider>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut embeddings = provider
+        .embed(&req.texts)
         .await
         .map_err(|_| warp::reject::custom(EmbeddingError::MLServiceError))?;
 
-    Ok(warp::reply::json(&result))
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    let mut ids = Vec::with_capacity(embeddings.len());
+    {
+        let mut store = store.write().unwrap();
+        for embedding in embeddings.iter_mut() {
+            embedding::normalize(embedding);
+            let id = Uuid::new_v4().to_string();
+            store.add_embedding(EmbeddingData {
+                id: id.clone(),
+                timestamp,
+                source: req.source.clone().unwrap_or_else(|| "text".to_string()),
+                embedding: embedding.clone(),
+                facets: req.facets.clone(),
+                confidence: 1.0,
+                source_ref: None,
+            });
+            ids.push(id);
+        }
+    }
+
+    Ok(warp::reply::json(&serde_json::json!({
+        "success": true,
+        "ids": ids,
+        "count": ids.len()
+    })))
 }
 
 #[derive(Debug)]
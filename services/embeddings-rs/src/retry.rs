@@ -0,0 +1,113 @@
+//! Retry/backoff subsystem for outbound HTTP calls — the self-hosted ML
+//! service today, and any future embedding-provider call. Distinguishes
+//! retryable failures (timeouts, connection errors, 5xx, 429/503) from
+//! fatal ones (other 4xx, deserialization errors) so a transient blip
+//! doesn't silently drop the whole request, but a malformed request
+//! doesn't retry forever either.
+
+use std::time::Duration;
+
+/// What to do after a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryStrategy {
+    /// Fatal — stop retrying and surface the error immediately.
+    GiveUp,
+    /// Transient — back off and try again.
+    Retry,
+    /// The server asked us to slow down (429/503) — back off longer.
+    RetryAfterRateLimit,
+}
+
+/// The outcome of one failed attempt: the error that caused it, plus what
+/// to do next.
+#[derive(Debug)]
+pub struct Retry {
+    pub error: anyhow::Error,
+    pub strategy: RetryStrategy,
+}
+
+impl Retry {
+    pub fn give_up(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            strategy: RetryStrategy::GiveUp,
+        }
+    }
+
+    pub fn retry(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            strategy: RetryStrategy::Retry,
+        }
+    }
+
+    pub fn retry_after_rate_limit(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            strategy: RetryStrategy::RetryAfterRateLimit,
+        }
+    }
+
+    /// Backoff duration for the `attempt`'th retry (0-indexed): `10^attempt`
+    /// ms normally, `100 + 10^attempt` ms when the server asked us to slow
+    /// down. `GiveUp` has no backoff — it hands the original error straight
+    /// back out.
+    pub fn into_duration(self, attempt: u32) -> Result<Duration, anyhow::Error> {
+        match self.strategy {
+            RetryStrategy::GiveUp => Err(self.error),
+            RetryStrategy::Retry => Ok(Duration::from_millis(10u64.saturating_pow(attempt))),
+            RetryStrategy::RetryAfterRateLimit => {
+                Ok(Duration::from_millis(100 + 10u64.saturating_pow(attempt)))
+            }
+        }
+    }
+}
+
+/// Classify a `reqwest` transport error (connection refused, timeout — as
+/// opposed to a non-2xx response, which `classify_status` handles).
+pub fn classify_transport_error(error: &reqwest::Error) -> RetryStrategy {
+    if error.is_timeout() || error.is_connect() {
+        RetryStrategy::Retry
+    } else {
+        RetryStrategy::GiveUp
+    }
+}
+
+/// Classify an HTTP response status as retryable or fatal.
+pub fn classify_status(status: reqwest::StatusCode) -> RetryStrategy {
+    if status.as_u16() == 429 || status.as_u16() == 503 {
+        RetryStrategy::RetryAfterRateLimit
+    } else if status.is_server_error() {
+        RetryStrategy::Retry
+    } else {
+        RetryStrategy::GiveUp
+    }
+}
+
+/// Run `attempt_fn` up to `max_attempts` times, sleeping the computed
+/// backoff between attempts. Returns the first success, or the last error
+/// once attempts are exhausted or a fatal `Retry::give_up` is returned.
+pub async fn with_retry<T, F, Fut>(
+    max_attempts: u32,
+    mut attempt_fn: F,
+) -> Result<T, anyhow::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, Retry>>,
+{
+    let mut attempt = 0;
+    loop {
+        let retry = match attempt_fn().await {
+            Ok(value) => return Ok(value),
+            Err(retry) => retry,
+        };
+
+        if retry.strategy == RetryStrategy::GiveUp || attempt + 1 >= max_attempts {
+            return Err(retry.error);
+        }
+
+        let duration = retry.into_duration(attempt)?;
+        tokio::time::sleep(duration).await;
+        attempt += 1;
+    }
+}
@@ -1,76 +1,172 @@
+use futures::StreamExt;
 use sentience::SentienceAgent;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fs;
-use std::io::Write;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, mpsc, Mutex as AsyncMutex};
 use warp::Filter;
 
-// Helper functions for speech affect analysis
-fn analyze_speech_valence(transcript: &str) -> f64 {
-    let lower = transcript.to_lowercase();
-    
-    // Positive words increase valence
-    let positive_words = ["happy", "good", "great", "awesome", "amazing", "wonderful", "excellent", "fantastic", "love", "like", "enjoy", "pleased", "excited", "thrilled", "delighted"];
-    let negative_words = ["sad", "bad", "terrible", "awful", "hate", "dislike", "angry", "mad", "frustrated", "disappointed", "upset", "worried", "scared", "afraid"];
-    
-    let mut valence: f64 = 0.5; // neutral starting point
-    
-    for word in &positive_words {
-        if lower.contains(word) {
-            valence += 0.1;
-        }
-    }
-    
-    for word in &negative_words {
-        if lower.contains(word) {
-            valence -= 0.1;
-        }
+// Helper functions for speech affect analysis.
+//
+// Scored token-by-token against a bundled valence/arousal lexicon instead
+// of `str::contains`, so "classic" doesn't trip on "sic" and "badminton"
+// doesn't trip on "bad". Negation ({not, no, never, n't} in the previous 3
+// tokens) reflects valence around neutral, and intensifiers/downtoners
+// ({very, extremely, really} / {slightly, somewhat} in the previous 3
+// tokens) scale a token's deviation from neutral (0.5).
+const SPEECH_AFFECT_LEXICON: &[(&str, f64, f64)] = &[
+    ("happy", 0.85, 0.6),
+    ("good", 0.7, 0.5),
+    ("great", 0.8, 0.6),
+    ("awesome", 0.85, 0.7),
+    ("amazing", 0.85, 0.75),
+    ("wonderful", 0.85, 0.6),
+    ("excellent", 0.8, 0.55),
+    ("fantastic", 0.85, 0.65),
+    ("love", 0.9, 0.6),
+    ("like", 0.65, 0.4),
+    ("enjoy", 0.75, 0.5),
+    ("pleased", 0.75, 0.45),
+    ("excited", 0.8, 0.85),
+    ("thrilled", 0.85, 0.85),
+    ("delighted", 0.85, 0.6),
+    ("sad", 0.15, 0.35),
+    ("bad", 0.2, 0.4),
+    ("terrible", 0.1, 0.6),
+    ("awful", 0.1, 0.6),
+    ("hate", 0.1, 0.65),
+    ("dislike", 0.25, 0.4),
+    ("angry", 0.15, 0.8),
+    ("mad", 0.15, 0.75),
+    ("frustrated", 0.2, 0.65),
+    ("disappointed", 0.2, 0.45),
+    ("upset", 0.2, 0.55),
+    ("worried", 0.25, 0.6),
+    ("scared", 0.2, 0.8),
+    ("afraid", 0.2, 0.75),
+    ("incredible", 0.8, 0.7),
+    ("wow", 0.6, 0.8),
+    ("intense", 0.5, 0.85),
+    ("crazy", 0.45, 0.85),
+    ("wild", 0.5, 0.8),
+    ("furious", 0.1, 0.9),
+    ("terrified", 0.1, 0.9),
+    ("shocked", 0.35, 0.85),
+    ("surprised", 0.55, 0.75),
+    ("calm", 0.65, 0.2),
+    ("peaceful", 0.7, 0.15),
+    ("quiet", 0.55, 0.2),
+    ("relaxed", 0.7, 0.2),
+    ("bored", 0.35, 0.2),
+    ("tired", 0.35, 0.25),
+    ("sleepy", 0.45, 0.15),
+    ("slow", 0.5, 0.25),
+    ("gentle", 0.6, 0.25),
+    ("soft", 0.55, 0.2),
+    ("mellow", 0.6, 0.2),
+    ("chill", 0.6, 0.2),
+];
+
+const NEGATION_WINDOW: usize = 3;
+
+fn tokenize_for_affect(transcript: &str) -> Vec<String> {
+    transcript
+        .split_whitespace()
+        .map(|raw| {
+            raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_lowercase()
+        })
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn is_negation_token(token: &str) -> bool {
+    matches!(token, "not" | "no" | "never") || token.ends_with("n't")
+}
+
+fn intensifier_scale(token: &str) -> Option<f64> {
+    match token {
+        "very" | "extremely" | "really" => Some(1.5),
+        "slightly" | "somewhat" => Some(0.5),
+        _ => None,
     }
-    
-    // Clamp between 0.0 and 1.0
-    valence.max(0.0_f64).min(1.0_f64)
 }
 
-fn analyze_speech_arousal(transcript: &str) -> f64 {
-    let lower = transcript.to_lowercase();
-    
-    // High arousal words
-    let high_arousal_words = ["excited", "thrilled", "amazing", "incredible", "wow", "oh my", "holy", "intense", "crazy", "wild", "furious", "angry", "scared", "terrified", "shocked", "surprised"];
-    let low_arousal_words = ["calm", "peaceful", "quiet", "relaxed", "bored", "tired", "sleepy", "slow", "gentle", "soft", "mellow", "chill"];
-    
-    let mut arousal: f64 = 0.5; // neutral starting point
-    
-    for word in &high_arousal_words {
-        if lower.contains(word) {
-            arousal += 0.15;
+/// Token-level valence/arousal scoring shared by `analyze_speech_valence`
+/// and `analyze_speech_arousal`. Returns `(valence, arousal)`, each the
+/// mean over matched tokens (defaulting to 0.5 when nothing matches) with
+/// negation and intensifier scaling applied, clamped to `[0.0, 1.0]`.
+fn analyze_speech_vad(transcript: &str) -> (f64, f64) {
+    let tokens = tokenize_for_affect(transcript);
+
+    let mut valence_sum = 0.0;
+    let mut arousal_sum = 0.0;
+    let mut matches = 0usize;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(&(_, base_valence, base_arousal)) =
+            SPEECH_AFFECT_LEXICON.iter().find(|(word, _, _)| word == token)
+        else {
+            continue;
+        };
+
+        let window_start = i.saturating_sub(NEGATION_WINDOW);
+        let window = &tokens[window_start..i];
+
+        let mut valence = base_valence;
+        let mut arousal = base_arousal;
+
+        if window.iter().any(|t| is_negation_token(t)) {
+            valence = 1.0 - valence;
         }
-    }
-    
-    for word in &low_arousal_words {
-        if lower.contains(word) {
-            arousal -= 0.15;
+
+        if let Some(scale) = window.iter().rev().find_map(|t| intensifier_scale(t)) {
+            valence = 0.5 + (valence - 0.5) * scale;
+            arousal = 0.5 + (arousal - 0.5) * scale;
         }
+
+        valence_sum += valence.clamp(0.0, 1.0);
+        arousal_sum += arousal.clamp(0.0, 1.0);
+        matches += 1;
     }
-    
-    // Check for exclamation marks and caps (indicators of high arousal)
-    if lower.contains('!') || transcript.chars().any(|c| c.is_uppercase()) {
+
+    if matches == 0 {
+        return (0.5, 0.5);
+    }
+
+    (
+        (valence_sum / matches as f64).clamp(0.0, 1.0),
+        (arousal_sum / matches as f64).clamp(0.0, 1.0),
+    )
+}
+
+fn analyze_speech_valence(transcript: &str) -> f64 {
+    analyze_speech_vad(transcript).0
+}
+
+fn analyze_speech_arousal(transcript: &str) -> f64 {
+    let (_, mut arousal) = analyze_speech_vad(transcript);
+
+    // Exclamation marks and caps are still a direct arousal signal,
+    // independent of the lexicon.
+    if transcript.contains('!') || transcript.chars().any(|c| c.is_uppercase()) {
         arousal += 0.1;
     }
-    
-    // Clamp between 0.0 and 1.0
-    arousal.max(0.0).min(1.0)
+
+    arousal.clamp(0.0, 1.0)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 struct TokenizeRequest {
     embedding_id: String,
     clip_topk: Option<Vec<ClipItem>>,
     transcript: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 struct ClipItem {
     label: String,
     score: f64,
@@ -78,11 +174,78 @@ struct ClipItem {
 
 #[derive(Serialize, Deserialize, Clone)]
 struct MemoryEvent {
+    /// Monotonic id, assigned at insert time, used as the SSE `id:` field so
+    /// `/memory/stream` clients can resume with `Last-Event-ID` after a
+    /// reconnect without losing or duplicating events. Absent on events
+    /// persisted before streaming support was added.
+    #[serde(default)]
+    seq: u64,
     ts: u64,
     embedding_id: String,
     embedding: Vec<f64>,
     facets: HashMap<String, serde_json::Value>,
     source: String, // "vision" or "speech"
+    /// SHA-256 of the canonicalized facets map (see `EventHash::of`), used
+    /// to recognize and collapse identical percepts and to give clients a
+    /// stable id for a token that survives a restart. Absent on events
+    /// persisted before hashing was added.
+    #[serde(default)]
+    content_hash: String,
+}
+
+/// Query params for `/memory/stream`: an alternative to `Last-Event-ID` for
+/// a client connecting for the first time that still wants recent history
+/// replayed before the live stream starts.
+#[derive(Deserialize)]
+struct MemoryStreamQuery {
+    since_ts: Option<u64>,
+}
+
+/// `/memory` response envelope: the matched events plus the state of the
+/// bounded `MEMORY_STORE` ring buffer they were drawn alongside, so clients
+/// can tell how close the active window is to evicting older context.
+#[derive(Serialize)]
+struct MemoryPageResponse {
+    events: Vec<MemoryEvent>,
+    used: usize,
+    capacity: usize,
+    remaining: usize,
+}
+
+/// Request body for `POST /relay`: the remote endpoint to push the token
+/// feed to.
+#[derive(Deserialize)]
+struct RelayRegistration {
+    url: String,
+}
+
+/// One delivery to a `/relay` subscriber: `"added"` for a newly produced
+/// token, `"retracted"` for one evicted from `MEMORY_STORE`. `seq` lets the
+/// remote side order and dedupe deliveries the same way `/memory/stream`
+/// clients do with `Last-Event-ID`.
+#[derive(Serialize, Clone)]
+struct RelayAssertion {
+    kind: &'static str,
+    seq: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<MemoryEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RecallRequest {
+    embedding: Vec<f64>,
+    top_k: Option<usize>,
+    #[serde(default)]
+    facets: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct RecallHit<'a> {
+    #[serde(flatten)]
+    event: &'a MemoryEvent,
+    score: f64,
 }
 
 #[derive(Serialize)]
@@ -92,55 +255,946 @@ struct SentienceToken {
     ts: u64,
     embedding_id: String,
     facets: HashMap<String, serde_json::Value>,
+    /// Steps taken by the agent action loop (tool name, input, result), in
+    /// order, so the UI can show the agent's reasoning chain. Empty when
+    /// the agent emitted no `action.*` keys to dispatch.
+    #[serde(default)]
+    trace: Vec<serde_json::Value>,
+    /// SHA-256 of the canonicalized facets map (see `EventHash::of`). Lets
+    /// a client reference this token by a stable, reproducible id instead
+    /// of its timestamp.
+    content_hash: String,
+    /// Anything `normalize_facets` had to clamp, re-type, or move under
+    /// `extra.*` while assembling `facets`. Empty on the common path; a
+    /// non-empty list doesn't mean the request failed, just that something
+    /// in it didn't match `FACET_SCHEMA` exactly.
+    #[serde(default)]
+    facet_errors: Vec<String>,
 }
 
-// Global Sentience agent instance
+/// Message fanned out to `/memory/stream` subscribers: either a newly
+/// inserted event, or a marker that the ring buffer dropped its oldest
+/// entry to make room, so UIs can show that older context has aged out of
+/// the active window (it's still durable in `MEMORY_DB`, just no longer in
+/// the bounded `MEMORY_STORE` cache or live recall).
+#[derive(Clone)]
+enum MemoryStreamMessage {
+    Event(MemoryEvent),
+    Evicted { seq: u64, content_hash: String, ts: u64 },
+}
 
 lazy_static::lazy_static! {
-    static ref SENTIENCE_AGENT: Arc<Mutex<SentienceAgent>> = Arc::new(Mutex::new(SentienceAgent::new()));
+    /// Bounded cache of the most recent events, for the SSE live/replay path
+    /// (`/memory/stream`), the `/recall` similarity scan, and the agent
+    /// action loop's `action.recall` - all of which want every embedding in
+    /// hand rather than paying a SQL round trip per tick. `MEMORY_DB` is the
+    /// durable store; this is just a warm window over its tail.
     static ref MEMORY_STORE: Arc<Mutex<VecDeque<MemoryEvent>>> = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+    /// Durable store for every `MemoryEvent`, indexed by `ts` and
+    /// `embedding_id` so `/memory` can push `limit`/`since_ts` down into an
+    /// indexed query instead of loading and sorting everything in memory.
+    static ref MEMORY_DB: Mutex<rusqlite::Connection> = {
+        let conn = rusqlite::Connection::open("data/memory.db")
+            .expect("failed to open data/memory.db");
+        run_memory_migrations(&conn).expect("failed to run memory store migrations");
+        Mutex::new(conn)
+    };
+    /// Fan-out for `/memory/stream`: every `MemoryEvent` pushed into
+    /// `MEMORY_STORE` is also sent here so connected SSE clients see it
+    /// live. Buffer is generous relative to expected subscriber count so a
+    /// briefly slow client sees a `Lagged` error (handled as a `memory.gap`
+    /// event) rather than blocking publishers.
+    static ref MEMORY_BROADCAST: broadcast::Sender<MemoryStreamMessage> = broadcast::channel(256).0;
+    /// Capacity of the `MEMORY_STORE` ring buffer (not `MEMORY_DB`, which
+    /// keeps full history). Configurable via `MEMORY_CAPACITY` so operators
+    /// can trade active-context size for memory without a rebuild; falls
+    /// back to the service's historical default of 500.
+    static ref MEMORY_CAPACITY: usize = std::env::var("MEMORY_CAPACITY")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(500);
 }
 
-fn escape_dsl(s: &str) -> String {
-    s.replace('\\', "\\\\").replace('"', "\\\"")
+/// Pool of independent `SentienceAgent` instances, each seeded from the
+/// same `agent.sentience` code, so concurrent `/run`/`/tokenize` calls
+/// don't serialize on one global agent. Agents are checked out over a
+/// bounded channel and run inside `spawn_blocking`, keeping the Tokio
+/// reactor free while the CPU-bound DSL tick executes. `MEMORY_STORE`
+/// stays the one shared synchronization point across agents.
+struct AgentPool {
+    agent_code: Arc<String>,
+    sender: mpsc::Sender<SentienceAgent>,
+    receiver: AsyncMutex<mpsc::Receiver<SentienceAgent>>,
 }
 
-fn add_to_memory(event: MemoryEvent) {
-    if let Ok(mut memory) = MEMORY_STORE.lock() {
-        // Add to ring buffer
-        if memory.len() >= 500 {
-            memory.pop_front();
+impl AgentPool {
+    fn new(agent_code: String, size: usize) -> Self {
+        let size = size.max(1);
+        let agent_code = Arc::new(agent_code);
+        let (sender, receiver) = mpsc::channel(size);
+        for _ in 0..size {
+            let mut agent = SentienceAgent::new();
+            let _ = agent.run_sentience(&agent_code);
+            let _ = sender.try_send(agent);
         }
-        memory.push_back(event.clone());
-        
-        // Persist to JSONL file
-        if let Ok(mut file) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open("data/memory.jsonl")
+        Self {
+            agent_code,
+            sender,
+            receiver: AsyncMutex::new(receiver),
+        }
+    }
+
+    async fn checkout(&self) -> SentienceAgent {
+        let mut receiver = self.receiver.lock().await;
+        receiver
+            .recv()
+            .await
+            .expect("agent pool channel closed unexpectedly")
+    }
+
+    async fn checkin(&self, agent: SentienceAgent) {
+        let _ = self.sender.send(agent).await;
+    }
+
+    /// Reseed a brand-new agent from the same code, for the rare case a
+    /// checked-out agent's `spawn_blocking` task panics and can't be
+    /// returned to the pool.
+    fn fresh_agent(&self) -> SentienceAgent {
+        let mut agent = SentienceAgent::new();
+        let _ = agent.run_sentience(&self.agent_code);
+        agent
+    }
+}
+
+fn cosine_similarity(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.is_empty() || a.len() != b.len() {
+        return None;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a * norm_b))
+}
+
+fn facet_overlap(a: &HashMap<String, serde_json::Value>, b: &HashMap<String, serde_json::Value>) -> usize {
+    a.iter().filter(|(k, v)| b.get(k.as_str()) == Some(*v)).count()
+}
+
+// Recency and facet-overlap weights for the recall reranking stage below;
+// RECALL_RECENCY_LAMBDA gives roughly a 30-minute half-life.
+const RECALL_RECENCY_LAMBDA: f64 = 0.0004;
+const RECALL_SIMILARITY_WEIGHT: f64 = 1.0;
+const RECALL_RECENCY_WEIGHT: f64 = 0.3;
+const RECALL_FACET_WEIGHT: f64 = 0.05;
+
+// Upper bound on agent action-loop iterations per request, so a
+// misbehaving agent that keeps emitting `action.*` keys can't tick forever.
+const MAX_AGENT_STEPS: usize = 4;
+
+/// Run the service-side tool an `action.*` key asked for and return the
+/// `tool.*` key to write the result back under, plus the result itself.
+/// `current_embedding`/`current_facets` are this request's own data, so
+/// e.g. `action.recall` can rank past events against what's happening now.
+fn dispatch_agent_tool(
+    action_key: &str,
+    action_value: &str,
+    current_embedding: &[f64],
+    current_facets: &HashMap<String, serde_json::Value>,
+) -> (String, String) {
+    match action_key {
+        "action.recall" => {
+            let result = match MEMORY_STORE.lock() {
+                Ok(memory) => {
+                    let mut hits: Vec<(&MemoryEvent, f64)> = memory
+                        .iter()
+                        .filter_map(|event| {
+                            cosine_similarity(current_embedding, &event.embedding)
+                                .map(|sim| (event, sim))
+                        })
+                        .collect();
+                    hits.sort_by(|a, b| {
+                        b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                    match hits.first() {
+                        Some((event, sim)) => format!(
+                            "most similar past {} event (similarity {:.2}): {:?}",
+                            event.source, sim, event.facets
+                        ),
+                        None => "no matching memory found".to_string(),
+                    }
+                }
+                Err(_) => "memory store unavailable".to_string(),
+            };
+            ("tool.recall".to_string(), result)
+        }
+        "action.describe_color" => {
+            let result = current_facets
+                .get("color.dominant")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            ("tool.describe_color".to_string(), result)
+        }
+        "action.embed" => (
+            "tool.embed".to_string(),
+            "embedding tool unavailable in id-rs".to_string(),
+        ),
+        other => (
+            format!("tool.{}", other.trim_start_matches("action.")),
+            format!("unsupported action: {}", action_value),
+        ),
+    }
+}
+
+/// The CPU-bound half of `/run`: build the DSL script from `req`, tick the
+/// agent, extract facets from its short-term memory, and run the bounded
+/// action loop from `dispatch_agent_tool`. Takes and returns an owned
+/// `SentienceAgent` so the caller can run this inside `spawn_blocking` and
+/// check the agent back into the pool afterwards.
+fn run_agent_pass(
+    mut agent: SentienceAgent,
+    req: &serde_json::Value,
+    embedding: Vec<f64>,
+    mut facets: HashMap<String, serde_json::Value>,
+) -> (SentienceAgent, HashMap<String, serde_json::Value>, Vec<serde_json::Value>) {
+    let mut trace: Vec<serde_json::Value> = Vec::new();
+
+    // Map incoming JSON to percept.* keys expected by the agent.
+    let transcript = req["transcript"].as_str().unwrap_or("");
+    let embedding_id = req["embedding_id"].as_str().unwrap_or("unknown");
+    let ctx = req["context"].as_str().unwrap_or("");
+
+    let t_esc = escape_dsl(transcript);
+    let ctx_esc = escape_dsl(ctx);
+
+    // Extremely lightweight intent/sentiment defaults (gateway may overwrite later).
+    let mut intent = "statement";
+    let sentiment = "neutral";
+    if !transcript.is_empty() {
+        let lower = transcript.to_lowercase();
+        if lower.contains('?')
+            || lower.starts_with("what ")
+            || lower.starts_with("who ")
+            || lower.starts_with("why ")
+            || lower.starts_with("how ")
+            || lower.starts_with("when ")
+            || lower.starts_with("where ")
         {
-            if let Ok(json) = serde_json::to_string(&event) {
-                let _ = writeln!(file, "{}", json);
-            }
+            intent = "question";
+        } else if lower.starts_with("hello") || lower.starts_with("hi") {
+            intent = "greeting";
         }
     }
+
+    // Check if we have vision data
+    let vision_object = req.get("vision_object").and_then(|v| v.as_str()).unwrap_or("");
+    let vision_color = req.get("vision_color").and_then(|v| v.as_str()).unwrap_or("");
+
+    let dsl = if !transcript.is_empty() {
+        // Speech + Vision
+        let vision_obj_line = if !vision_object.is_empty() {
+            format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(vision_object))
+        } else { String::new() };
+        let vision_col_line = if !vision_color.is_empty() {
+            format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", escape_dsl(vision_color))
+        } else { String::new() };
+
+        format!(
+            ".use \"MultiModalWriter\"\n\
+             mem.short[\"percept.context\"] = \"{ctx}\"\n\
+             {vision_obj}{vision_col}mem.short[\"percept.speech.transcript\"] = \"{t}\"\n\
+             mem.short[\"percept.speech.intent\"] = \"{intent}\"\n\
+             mem.short[\"percept.speech.sentiment\"] = \"{sentiment}\"\n\
+             mem.short[\"percept.affect.valence\"] = 0.5\n\
+             mem.short[\"percept.affect.arousal\"] = 0.3\n\
+             mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
+             .input \"tick\"",
+            ctx = ctx_esc,
+            vision_obj = vision_obj_line,
+            vision_col = vision_col_line,
+            t = t_esc,
+            intent = intent,
+            sentiment = sentiment,
+            emb = escape_dsl(embedding_id),
+        )
+    } else {
+        // Vision only
+        let vision_obj_line = if !vision_object.is_empty() {
+            format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(vision_object))
+        } else { String::new() };
+        let vision_col_line = if !vision_color.is_empty() {
+            format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", escape_dsl(vision_color))
+        } else { String::new() };
+
+        format!(
+            ".use \"MultiModalWriter\"\n\
+             mem.short[\"percept.context\"] = \"{ctx}\"\n\
+             {vision_obj}{vision_col}mem.short[\"percept.affect.valence\"] = 0.5\n\
+             mem.short[\"percept.affect.arousal\"] = 0.3\n\
+             mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
+             .input \"tick\"",
+            ctx = ctx_esc,
+            vision_obj = vision_obj_line,
+            vision_col = vision_col_line,
+            emb = escape_dsl(embedding_id),
+        )
+    };
+    println!("Sending to Sentience via DSL script:\n{}", dsl);
+    let _ = agent.run_sentience(&dsl);
+
+    // Debug: print all memory
+    let short_mem = agent.all_short();
+    println!("Short memory: {:?}", short_mem);
+
+    // Extract facets from agent's short-term memory. Values are kept as the
+    // raw strings the agent wrote - `normalize_facets` is the single place
+    // that parses them against `FACET_SCHEMA`'s expected type.
+    for (key, value) in short_mem.clone() {
+        if key.starts_with("facets.") {
+            let facet_key = key.strip_prefix("facets.").unwrap_or(&key);
+            facets.insert(facet_key.to_string(), serde_json::Value::String(value));
+        }
+    }
+
+    // Fallback/augment: map any remaining percept.* keys the agent left in memory
+    {
+        if let Some(obj) = short_mem.get("percept.vision.object") {
+            facets
+                .entry("vision.object".into())
+                .or_insert_with(|| serde_json::Value::String(obj.clone()));
+        }
+        if let Some(col) = short_mem.get("percept.vision.color") {
+            facets
+                .entry("color.dominant".into())
+                .or_insert_with(|| serde_json::Value::String(col.clone()));
+        }
+        if let Some(tr) = short_mem.get("percept.speech.transcript") {
+            facets
+                .entry("speech.transcript".into())
+                .or_insert_with(|| serde_json::Value::String(tr.clone()));
+        }
+        if let Some(inten) = short_mem.get("percept.speech.intent") {
+            facets
+                .entry("speech.intent".into())
+                .or_insert_with(|| serde_json::Value::String(inten.clone()));
+        }
+        if let Some(sent) = short_mem.get("percept.speech.sentiment") {
+            facets
+                .entry("speech.sentiment".into())
+                .or_insert_with(|| serde_json::Value::String(sent.clone()));
+        }
+        if let Some(v) = short_mem.get("percept.affect.valence") {
+            facets
+                .entry("affect.valence".into())
+                .or_insert_with(|| serde_json::Value::String(v.clone()));
+        }
+        if let Some(a) = short_mem.get("percept.affect.arousal") {
+            facets
+                .entry("affect.arousal".into())
+                .or_insert_with(|| serde_json::Value::String(a.clone()));
+        }
+    }
+
+    // Bounded multi-step action loop: after each tick, dispatch any
+    // `action.*` key the agent emitted to the matching service-side tool,
+    // write the result back under `tool.*`, and re-tick so the agent can
+    // react to it.
+    for step in 0..MAX_AGENT_STEPS {
+        let actions: Vec<(String, String)> = agent
+            .all_short()
+            .into_iter()
+            .filter(|(key, _)| key.starts_with("action."))
+            .collect();
+        if actions.is_empty() {
+            break;
+        }
+
+        for (action_key, action_value) in actions {
+            let (tool_key, result) =
+                dispatch_agent_tool(&action_key, &action_value, &embedding, &facets);
+            trace.push(serde_json::json!({
+                "step": step,
+                "action": action_key,
+                "input": action_value,
+                "tool": tool_key,
+                "result": result,
+            }));
+
+            let tool_dsl = format!(
+                "mem.short[\"{}\"] = \"{}\"\n.input \"tick\"",
+                tool_key,
+                escape_dsl(&result)
+            );
+            let _ = agent.run_sentience(&tool_dsl);
+        }
+    }
+
+    (agent, facets, trace)
 }
 
-fn load_memory_from_file() {
-    if let Ok(content) = std::fs::read_to_string("data/memory.jsonl") {
-        if let Ok(mut memory) = MEMORY_STORE.lock() {
-            for line in content.lines() {
-                if let Ok(event) = serde_json::from_str::<MemoryEvent>(line) {
-                    if memory.len() >= 500 {
-                        memory.pop_front();
+/// The CPU-bound half of `/tokenize`: build the DSL script from `req`, tick
+/// the agent, extract facets from its short-term memory, and run the same
+/// bounded action loop `run_agent_pass` does. Same owned-agent-in/
+/// owned-agent-out shape as `run_agent_pass`, for the same `spawn_blocking`
+/// reason. `/tokenize` has no embedding in its request body, so the action
+/// loop's similarity-dependent tools (e.g. `action.recall`) run against an
+/// empty embedding here.
+fn run_tokenize_pass(
+    mut agent: SentienceAgent,
+    req: &TokenizeRequest,
+) -> (SentienceAgent, HashMap<String, serde_json::Value>, Vec<serde_json::Value>) {
+    let mut facets = HashMap::new();
+    let mut trace: Vec<serde_json::Value> = Vec::new();
+
+    // Build a DSL snippet that seeds percept.* keys the agent expects.
+    // Vision (pick top-1 label if present)
+    let mut top_label: Option<String> = None;
+    if let Some(clip_topk) = &req.clip_topk {
+        if let Some(first) = clip_topk.first() {
+            top_label = Some(first.label.clone());
+        }
+    }
+
+    // Heuristic color from label (demo only)
+    let color = match top_label.as_deref() {
+        Some("banana") => "yellow",
+        Some("apple") => "red",
+        _ => "unknown",
+    };
+
+    let t = req.transcript.as_deref().unwrap_or("");
+    let t_esc = escape_dsl(t);
+    let emb_esc = escape_dsl(&req.embedding_id);
+
+    // Tiny default intent/sentiment; upstream can get smarter later.
+    let lower = t.to_lowercase();
+    let mut intent = "statement";
+    let sentiment = "neutral";
+    if !t.is_empty() {
+        if lower.contains('?') || lower.starts_with("what ") || lower.starts_with("who ")
+            || lower.starts_with("why ") || lower.starts_with("how ")
+            || lower.starts_with("when ") || lower.starts_with("where ") {
+            intent = "question";
+        } else if lower.starts_with("hello") || lower.starts_with("hi") {
+            intent = "greeting";
+        }
+    }
+
+    let dsl = format!(
+        ".use \"MultiModalWriter\"\n\
+         {vision_obj}{vision_color}mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
+         mem.short[\"percept.speech.transcript\"] = \"{t}\"\n\
+         mem.short[\"percept.speech.intent\"] = \"{intent}\"\n\
+         mem.short[\"percept.speech.sentiment\"] = \"{sentiment}\"\n\
+         mem.short[\"percept.affect.valence\"] = 0.5\n\
+         mem.short[\"percept.affect.arousal\"] = 0.3\n\
+         .input \"tick\"",
+        emb = emb_esc,
+        t = t_esc,
+        intent = intent,
+        sentiment = sentiment,
+        vision_obj = if let Some(lbl) = &top_label {
+            format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(lbl))
+        } else { String::new() },
+        vision_color = if top_label.is_some() {
+            format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", color)
+        } else { String::new() },
+    );
+
+    println!("Sending to Sentience via DSL script:\n{}", dsl);
+    let _ = agent.run_sentience(&dsl);
+
+    // Debug: print all memory
+    let short_mem = agent.all_short();
+    println!("Short memory: {:?}", short_mem);
+
+    // Extract facets from agent's short-term memory. Values are kept as the
+    // raw strings the agent wrote - `normalize_facets` is the single place
+    // that parses them against `FACET_SCHEMA`'s expected type.
+    for (key, value) in short_mem.clone() {
+        if key.starts_with("vision")
+            || key.starts_with("speech")
+            || key.starts_with("affect")
+            || key.starts_with("color")
+        {
+            facets.insert(key, serde_json::Value::String(value));
+        }
+    }
+
+    // Merge explicit facets.* keys written by the agent
+    for (k, v) in short_mem.iter() {
+        if let Some(stripped) = k.strip_prefix("facets.") {
+            facets.insert(stripped.to_string(), serde_json::Value::String(v.clone()));
+        }
+    }
+
+    // Fallback/augment: map any remaining percept.* keys the agent left in memory
+    {
+        if let Some(obj) = short_mem.get("percept.vision.object") {
+            facets.entry("vision.object".into()).or_insert_with(|| serde_json::Value::String(obj.clone()));
+        }
+        if let Some(col) = short_mem.get("percept.vision.color") {
+            facets.entry("color.dominant".into()).or_insert_with(|| serde_json::Value::String(col.clone()));
+        }
+        if let Some(tr) = short_mem.get("percept.speech.transcript") {
+            facets.entry("speech.transcript".into()).or_insert_with(|| serde_json::Value::String(tr.clone()));
+        }
+        if let Some(inten) = short_mem.get("percept.speech.intent") {
+            facets.entry("speech.intent".into()).or_insert_with(|| serde_json::Value::String(inten.clone()));
+        }
+        if let Some(sent) = short_mem.get("percept.speech.sentiment") {
+            facets.entry("speech.sentiment".into()).or_insert_with(|| serde_json::Value::String(sent.clone()));
+        }
+        if let Some(v) = short_mem.get("percept.affect.valence") {
+            facets.entry("affect.valence".into()).or_insert_with(|| serde_json::Value::String(v.clone()));
+        }
+        if let Some(a) = short_mem.get("percept.affect.arousal") {
+            facets.entry("affect.arousal".into()).or_insert_with(|| serde_json::Value::String(a.clone()));
+        }
+    }
+
+    // Bounded multi-step action loop: after each tick, dispatch any
+    // `action.*` key the agent emitted to the matching service-side tool,
+    // write the result back under `tool.*`, and re-tick so the agent can
+    // react to it. Same shape as `run_agent_pass`'s loop.
+    for step in 0..MAX_AGENT_STEPS {
+        let actions: Vec<(String, String)> = agent
+            .all_short()
+            .into_iter()
+            .filter(|(key, _)| key.starts_with("action."))
+            .collect();
+        if actions.is_empty() {
+            break;
+        }
+
+        for (action_key, action_value) in actions {
+            let (tool_key, result) =
+                dispatch_agent_tool(&action_key, &action_value, &[], &facets);
+            trace.push(serde_json::json!({
+                "step": step,
+                "action": action_key,
+                "input": action_value,
+                "tool": tool_key,
+                "result": result,
+            }));
+
+            let tool_dsl = format!(
+                "mem.short[\"{}\"] = \"{}\"\n.input \"tick\"",
+                tool_key,
+                escape_dsl(&result)
+            );
+            let _ = agent.run_sentience(&tool_dsl);
+        }
+    }
+
+    (agent, facets, trace)
+}
+
+// Sanitize untrusted text before it's interpolated into a DSL script line.
+// The whole line is executed by `agent.run_sentience`, so a transcript
+// containing a raw `\n` could close the current statement and inject new
+// directives. It drops every control character (including
+// `\n`, `\r`, `\t`) so no interpolated value can carry a line break, then
+// neutralizes the directive tokens (`.use`, `.input`, `mem.short`) a payload
+// smuggled past that filter would need to start a new statement, before
+// finally escaping the quote the result is interpolated into.
+fn escape_dsl(s: &str) -> String {
+    let sanitized: String = s.chars().filter(|c| !c.is_control()).collect();
+    let sanitized = sanitized
+        .replace(".use", "_use")
+        .replace(".input", "_input")
+        .replace("mem.short", "mem_short");
+    sanitized.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Computes the content-addressed id for a facets map. BTreeMap-orders the
+/// keys before compact-serializing so the hash is deterministic across
+/// HashMap iteration order and process restarts - the same percept always
+/// hashes the same way.
+struct EventHash;
+
+impl EventHash {
+    fn of(facets: &HashMap<String, serde_json::Value>) -> String {
+        let canonical: BTreeMap<&String, &serde_json::Value> = facets.iter().collect();
+        let bytes = serde_json::to_vec(&canonical).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Expected shape for one canonical facet key. `Number`'s bounds are
+/// enforced by clamping rather than rejection - a heuristic affect score
+/// landing just outside [-1, 1] is still useful once pulled back in range,
+/// unlike a facet of the wrong type entirely.
+enum FacetType {
+    Text,
+    Number { min: f64, max: f64 },
+    Enum(&'static [&'static str]),
+}
+
+lazy_static::lazy_static! {
+    /// Canonical facet keys this service understands, and how to validate
+    /// each one. A key that isn't listed here isn't dropped - `normalize_facets`
+    /// re-homes it under `extra.*` - so a new percept type a caller is
+    /// experimenting with still reaches the UI, just visibly unvalidated.
+    static ref FACET_SCHEMA: HashMap<&'static str, FacetType> = {
+        let mut m: HashMap<&'static str, FacetType> = HashMap::new();
+        m.insert("vision.object", FacetType::Text);
+        m.insert("color.dominant", FacetType::Text);
+        m.insert("speech.transcript", FacetType::Text);
+        m.insert(
+            "speech.intent",
+            FacetType::Enum(&["statement", "question", "greeting", "unknown"]),
+        );
+        m.insert(
+            "speech.sentiment",
+            FacetType::Enum(&["positive", "neutral", "negative"]),
+        );
+        m.insert("affect.valence", FacetType::Number { min: -1.0, max: 1.0 });
+        m.insert("affect.arousal", FacetType::Number { min: 0.0, max: 1.0 });
+        m
+    };
+}
+
+/// Single choke point every facet merge (explicit `facets.*`, `percept.*`
+/// fallback, CLIP top-k, transcript heuristics) is routed through before a
+/// `SentienceToken`/`MemoryEvent` is built. Callers hand it raw values
+/// straight off the agent's short-term memory (always strings) rather than
+/// pre-parsing them, so this is also the one place that turns a schema-known
+/// key's string into its typed `FacetType` - downstream consumers only ever
+/// see range-checked, correctly-typed values. Returns the normalized map
+/// plus a human-readable description of anything it had to clamp, re-type,
+/// or re-home - callers surface those on `SentienceToken::facet_errors`
+/// rather than failing the request, since a malformed facet shouldn't block
+/// the ones that parsed fine.
+fn normalize_facets(
+    facets: HashMap<String, serde_json::Value>,
+) -> (HashMap<String, serde_json::Value>, Vec<String>) {
+    let mut out = HashMap::with_capacity(facets.len());
+    let mut errors = Vec::new();
+
+    for (key, value) in facets {
+        match FACET_SCHEMA.get(key.as_str()) {
+            Some(FacetType::Text) => match value.as_str() {
+                Some(s) => {
+                    out.insert(key, serde_json::Value::String(s.to_string()));
+                }
+                None => {
+                    errors.push(format!("{}: expected a string, got {}", key, value));
+                    out.insert(format!("extra.{}", key), value);
+                }
+            },
+            Some(FacetType::Number { min, max }) => {
+                // Accept either an already-numeric value or the string form
+                // the agent's short-term memory always produces.
+                let parsed = value
+                    .as_f64()
+                    .or_else(|| value.as_str().and_then(|s| s.parse::<f64>().ok()));
+                match parsed {
+                    Some(n) => {
+                        let clamped = n.clamp(*min, *max);
+                        if clamped != n {
+                            errors.push(format!(
+                                "{}: {} out of range [{}, {}], clamped to {}",
+                                key, n, min, max, clamped
+                            ));
+                        }
+                        out.insert(key, serde_json::json!(clamped));
                     }
-                    memory.push_back(event);
+                    None => {
+                        errors.push(format!("{}: expected a number, got {}", key, value));
+                        out.insert(format!("extra.{}", key), value);
+                    }
+                }
+            }
+            Some(FacetType::Enum(allowed)) => match value.as_str() {
+                Some(s) if allowed.contains(&s) => {
+                    out.insert(key, serde_json::Value::String(s.to_string()));
                 }
+                Some(s) => {
+                    errors.push(format!("{}: '{}' is not one of {:?}", key, s, allowed));
+                    out.insert(format!("extra.{}", key), serde_json::Value::String(s.to_string()));
+                }
+                None => {
+                    errors.push(format!("{}: expected a string, got {}", key, value));
+                    out.insert(format!("extra.{}", key), value);
+                }
+            },
+            None => {
+                out.insert(format!("extra.{}", key), value);
+            }
+        }
+    }
+
+    (out, errors)
+}
+
+/// Render a stored `MemoryEvent` as an SSE frame, stamping the `id:` field
+/// with its sequence number so a reconnecting client's `Last-Event-ID`
+/// tells us exactly where to resume.
+fn memory_event_to_sse(event: &MemoryEvent) -> warp::sse::Event {
+    let payload = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
+    warp::sse::Event::default()
+        .id(event.seq.to_string())
+        .data(payload)
+}
+
+// How far back to look for a duplicate `content_hash` before inserting.
+// Bounded rather than store-wide so an old, legitimately-repeated percept
+// (e.g. the same idle scene an hour later) isn't silently collapsed into
+// a much earlier event.
+const CONTENT_DEDUP_WINDOW: usize = 20;
+
+/// Schema for the durable `memory_events` table, applied idempotently at
+/// startup (this service has only ever had one schema version, so there's
+/// no `migrations/` directory to walk yet - just the one `CREATE TABLE IF
+/// NOT EXISTS`). Indexed on `ts` for `/memory`'s time-range scans and on
+/// `content_hash` for the `?hash=` point lookup.
+fn run_memory_migrations(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS memory_events (
+            seq INTEGER PRIMARY KEY,
+            ts INTEGER NOT NULL,
+            embedding_id TEXT NOT NULL,
+            embedding TEXT NOT NULL,
+            facets TEXT NOT NULL,
+            source TEXT NOT NULL,
+            content_hash TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_memory_events_ts ON memory_events(ts);
+        CREATE INDEX IF NOT EXISTS idx_memory_events_embedding_id ON memory_events(embedding_id);
+        CREATE INDEX IF NOT EXISTS idx_memory_events_content_hash ON memory_events(content_hash);",
+    )
+}
+
+fn row_to_memory_event(row: &rusqlite::Row) -> rusqlite::Result<MemoryEvent> {
+    let embedding_json: String = row.get("embedding")?;
+    let facets_json: String = row.get("facets")?;
+    Ok(MemoryEvent {
+        seq: row.get::<_, i64>("seq")? as u64,
+        ts: row.get::<_, i64>("ts")? as u64,
+        embedding_id: row.get("embedding_id")?,
+        embedding: serde_json::from_str(&embedding_json).unwrap_or_default(),
+        facets: serde_json::from_str(&facets_json).unwrap_or_default(),
+        source: row.get("source")?,
+        content_hash: row.get("content_hash")?,
+    })
+}
+
+fn add_to_memory(mut event: MemoryEvent) {
+    let mut memory = match MEMORY_STORE.lock() {
+        Ok(memory) => memory,
+        Err(_) => return,
+    };
+
+    // Identical percept as one of the last few events: collapse instead of
+    // piling up another near-duplicate entry.
+    if memory
+        .iter()
+        .rev()
+        .take(CONTENT_DEDUP_WINDOW)
+        .any(|e| !e.content_hash.is_empty() && e.content_hash == event.content_hash)
+    {
+        return;
+    }
+
+    // Durable insert first, so `seq` (the row's autoincrement id) survives
+    // process restarts instead of resetting to 1.
+    let seq = {
+        let db = match MEMORY_DB.lock() {
+            Ok(db) => db,
+            Err(_) => return,
+        };
+        let inserted = db.execute(
+            "INSERT INTO memory_events (ts, embedding_id, embedding, facets, source, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                event.ts as i64,
+                event.embedding_id,
+                serde_json::to_string(&event.embedding).unwrap_or_default(),
+                serde_json::to_string(&event.facets).unwrap_or_default(),
+                event.source,
+                event.content_hash,
+            ],
+        );
+        match inserted {
+            Ok(_) => db.last_insert_rowid() as u64,
+            Err(e) => {
+                eprintln!("Failed to persist memory event to data/memory.db: {}", e);
+                return;
+            }
+        }
+    };
+    event.seq = seq;
+
+    // Add to the bounded in-memory cache, evicting the oldest entry once
+    // MEMORY_CAPACITY is reached. The evicted event is already durable in
+    // MEMORY_DB - only its presence in the active window (cache, /recall,
+    // live SSE) is gone.
+    let evicted = if memory.len() >= *MEMORY_CAPACITY {
+        memory.pop_front()
+    } else {
+        None
+    };
+    memory.push_back(event.clone());
+    drop(memory);
+
+    // Fan out to live /memory/stream subscribers. Err means no receivers
+    // are currently connected, which is fine - the event is still durable
+    // in MEMORY_DB for a later replay.
+    let _ = MEMORY_BROADCAST.send(MemoryStreamMessage::Event(event));
+    if let Some(evicted) = evicted {
+        let _ = MEMORY_BROADCAST.send(MemoryStreamMessage::Evicted {
+            seq: evicted.seq,
+            content_hash: evicted.content_hash,
+            ts: evicted.ts,
+        });
+    }
+}
+
+/// Warm `MEMORY_STORE`'s in-memory cache from the tail of the durable
+/// store at startup, so `/recall`, the agent action loop, and a
+/// reconnecting `/memory/stream` client all see recent history immediately
+/// rather than waiting for it to be re-derived from new events.
+fn load_memory_cache_from_db() {
+    let db = match MEMORY_DB.lock() {
+        Ok(db) => db,
+        Err(_) => return,
+    };
+    let mut stmt = match db.prepare(&format!(
+        "SELECT * FROM memory_events ORDER BY seq DESC LIMIT {}",
+        *MEMORY_CAPACITY
+    )) {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            eprintln!("Failed to prepare memory cache warm-up query: {}", e);
+            return;
+        }
+    };
+    let rows = match stmt.query_map([], row_to_memory_event) {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Failed to warm memory cache from data/memory.db: {}", e);
+            return;
+        }
+    };
+
+    let mut recent: Vec<MemoryEvent> = rows.filter_map(Result::ok).collect();
+    recent.reverse(); // oldest-first, matching MEMORY_STORE's push_back order
+
+    if let Ok(mut memory) = MEMORY_STORE.lock() {
+        memory.extend(recent);
+    }
+}
+
+/// POST `assertion` to `target`, retrying with a fixed backoff on failure
+/// instead of dropping it - a transient network blip on the remote side
+/// shouldn't lose an event. The remote is expected to dedupe by `seq` in
+/// case a retry lands after a delayed success already got through.
+async fn deliver_relay_assertion(client: &reqwest::Client, target: &str, assertion: &RelayAssertion) {
+    loop {
+        match client.post(target).json(assertion).send().await {
+            Ok(resp) if resp.status().is_success() => return,
+            Ok(resp) => eprintln!("Relay POST to {} rejected: {}", target, resp.status()),
+            Err(e) => eprintln!("Relay POST to {} failed: {}", target, e),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
+
+/// Replay every durable event with `seq > since` to `target`, oldest
+/// first, as `"added"` assertions - used after a relay subscriber falls
+/// behind (`Lagged`) so the gap it missed on the live feed is backfilled
+/// from `MEMORY_DB` instead of silently lost.
+async fn replay_relay_gap(client: &reqwest::Client, target: &str, since: u64) {
+    let events: Vec<MemoryEvent> = {
+        let db = match MEMORY_DB.lock() {
+            Ok(db) => db,
+            Err(_) => return,
+        };
+        let mut stmt = match db.prepare("SELECT * FROM memory_events WHERE seq > ?1 ORDER BY seq ASC") {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                eprintln!("Relay replay query failed: {}", e);
+                return;
+            }
+        };
+        match stmt.query_map(rusqlite::params![since as i64], row_to_memory_event) {
+            Ok(rows) => rows.filter_map(Result::ok).collect(),
+            Err(e) => {
+                eprintln!("Relay replay read failed: {}", e);
+                Vec::new()
             }
         }
+    };
+    for event in events {
+        let assertion = RelayAssertion {
+            kind: "added",
+            seq: event.seq,
+            event: Some(event),
+            content_hash: None,
+        };
+        deliver_relay_assertion(client, target, &assertion).await;
     }
 }
 
+/// Background task for one `/relay` subscriber: mirrors the exact ordered
+/// feed that drives `/memory/stream` (subscribing to `MEMORY_BROADCAST`) as
+/// a series of HTTP POSTs to `target`, turning `MemoryStreamMessage::Event`
+/// into an `"added"` assertion and `::Evicted` into a `"retracted"` one. On
+/// `Lagged`, replays the missed range from `MEMORY_DB` before resuming the
+/// live feed - the same replay-then-live shape `/memory/stream` uses for a
+/// reconnecting `Last-Event-ID` client, just pushed instead of pulled.
+fn spawn_relay_task(target: String) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut receiver = MEMORY_BROADCAST.subscribe();
+        let mut last_seq = MEMORY_STORE
+            .lock()
+            .map(|m| m.back().map(|e| e.seq).unwrap_or(0))
+            .unwrap_or(0);
+
+        loop {
+            match receiver.recv().await {
+                Ok(MemoryStreamMessage::Event(event)) => {
+                    let seq = event.seq;
+                    let assertion = RelayAssertion {
+                        kind: "added",
+                        seq,
+                        event: Some(event),
+                        content_hash: None,
+                    };
+                    deliver_relay_assertion(&client, &target, &assertion).await;
+                    last_seq = seq;
+                }
+                Ok(MemoryStreamMessage::Evicted { seq, content_hash, .. }) => {
+                    let assertion = RelayAssertion {
+                        kind: "retracted",
+                        seq,
+                        event: None,
+                        content_hash: Some(content_hash),
+                    };
+                    deliver_relay_assertion(&client, &target, &assertion).await;
+                    last_seq = last_seq.max(seq);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    replay_relay_gap(&client, &target, last_seq).await;
+                    last_seq = MEMORY_STORE
+                        .lock()
+                        .map(|m| m.back().map(|e| e.seq).unwrap_or(last_seq))
+                        .unwrap_or(last_seq);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 #[tokio::main]
 async fn main() {
     println!("Sentience service starting on :8082");
@@ -149,9 +1203,9 @@ async fn main() {
     // Create data directory if it doesn't exist
     let _ = std::fs::create_dir_all("data");
 
-    // Load memory from file
-    load_memory_from_file();
-    println!("Loaded memory from data/memory.jsonl");
+    // Load memory cache from the durable store
+    load_memory_cache_from_db();
+    println!("Loaded memory cache from data/memory.db");
 
     // Load the Sentience agent from file
     let agent_code = match fs::read_to_string("agent.sentience") {
@@ -187,11 +1241,15 @@ agent MultiModalAnalyzer {
         }
     };
 
-    // Register the agent
-    if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
-        let _ = agent.run_sentience(&agent_code);
-        println!("Sentience agent registered from agent.sentience");
-    }
+    // Build a pool of agents (one per available CPU), each seeded from the
+    // same agent.sentience code, instead of one agent shared by every
+    // concurrent request.
+    let pool_size = num_cpus::get();
+    let agent_pool = Arc::new(AgentPool::new(agent_code, pool_size));
+    println!(
+        "Sentience agent pool of {} registered from agent.sentience",
+        pool_size
+    );
 
     let cors = warp::cors()
         .allow_any_origin()
@@ -219,10 +1277,13 @@ agent MultiModalAnalyzer {
         }))
     });
 
+    let run_agent_pool = agent_pool.clone();
     let run = warp::path("run")
         .and(warp::post())
         .and(warp::body::json())
-        .map(|req: serde_json::Value| {
+        .and_then(move |req: serde_json::Value| {
+            let agent_pool = run_agent_pool.clone();
+            async move {
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -306,157 +1367,30 @@ agent MultiModalAnalyzer {
                 facets.insert("affect.arousal".into(), serde_json::json!(arousal));
             }
 
-            // Use real Sentience agent to analyze input
-            if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
-                // Map incoming JSON to percept.* keys expected by the agent.
-                let transcript = req["transcript"].as_str().unwrap_or("");
-                let embedding_id = req["embedding_id"].as_str().unwrap_or("unknown");
-                let ctx = req["context"].as_str().unwrap_or("");
-
-                let t_esc = escape_dsl(transcript);
-                let ctx_esc = escape_dsl(ctx);
-
-                // Extremely lightweight intent/sentiment defaults (gateway may overwrite later).
-                let mut intent = "statement";
-                let sentiment = "neutral";
-                if !transcript.is_empty() {
-                    let lower = transcript.to_lowercase();
-                    if lower.contains('?')
-                        || lower.starts_with("what ")
-                        || lower.starts_with("who ")
-                        || lower.starts_with("why ")
-                        || lower.starts_with("how ")
-                        || lower.starts_with("when ")
-                        || lower.starts_with("where ")
-                    {
-                        intent = "question";
-                    } else if lower.starts_with("hello") || lower.starts_with("hi") {
-                        intent = "greeting";
-                    }
-                }
+            // Computed up front (rather than down by add_to_memory) so the
+            // agent action loop below can use it for action.recall.
+            let embedding: Vec<f64> = req
+                .get("embedding")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
+                .unwrap_or_default();
 
-                // Check if we have vision data
-                let vision_object = req.get("vision_object").and_then(|v| v.as_str()).unwrap_or("");
-                let vision_color = req.get("vision_color").and_then(|v| v.as_str()).unwrap_or("");
-                
-                let dsl = if !transcript.is_empty() {
-                    // Speech + Vision
-                    let vision_obj_line = if !vision_object.is_empty() {
-                        format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(vision_object))
-                    } else { String::new() };
-                    let vision_col_line = if !vision_color.is_empty() {
-                        format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", escape_dsl(vision_color))
-                    } else { String::new() };
-                    
-                    format!(
-                        ".use \"MultiModalWriter\"\n\
-                         mem.short[\"percept.context\"] = \"{ctx}\"\n\
-                         {vision_obj}{vision_col}mem.short[\"percept.speech.transcript\"] = \"{t}\"\n\
-                         mem.short[\"percept.speech.intent\"] = \"{intent}\"\n\
-                         mem.short[\"percept.speech.sentiment\"] = \"{sentiment}\"\n\
-                         mem.short[\"percept.affect.valence\"] = 0.5\n\
-                         mem.short[\"percept.affect.arousal\"] = 0.3\n\
-                         mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
-                         .input \"tick\"",
-                        ctx = ctx_esc,
-                        vision_obj = vision_obj_line,
-                        vision_col = vision_col_line,
-                        t = t_esc,
-                        intent = intent,
-                        sentiment = sentiment,
-                        emb = escape_dsl(embedding_id),
-                    )
-                } else {
-                    // Vision only
-                    let vision_obj_line = if !vision_object.is_empty() {
-                        format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(vision_object))
-                    } else { String::new() };
-                    let vision_col_line = if !vision_color.is_empty() {
-                        format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", escape_dsl(vision_color))
-                    } else { String::new() };
-                    
-                    format!(
-                        ".use \"MultiModalWriter\"\n\
-                         mem.short[\"percept.context\"] = \"{ctx}\"\n\
-                         {vision_obj}{vision_col}mem.short[\"percept.affect.valence\"] = 0.5\n\
-                         mem.short[\"percept.affect.arousal\"] = 0.3\n\
-                         mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
-                         .input \"tick\"",
-                        ctx = ctx_esc,
-                        vision_obj = vision_obj_line,
-                        vision_col = vision_col_line,
-                        emb = escape_dsl(embedding_id),
-                    )
-                };
-                println!("Sending to Sentience via DSL script:\n{}", dsl);
-                let _ = agent.run_sentience(&dsl);
-
-                // Debug: print all memory
-                let short_mem = agent.all_short();
-                println!("Short memory: {:?}", short_mem);
-
-                // Extract facets from agent's short-term memory
-                for (key, value) in short_mem.clone() {
-                    if key.starts_with("facets.") {
-                        let facet_key = key.strip_prefix("facets.").unwrap_or(&key);
-                        if let Ok(num) = value.parse::<f64>() {
-                            facets.insert(
-                                facet_key.to_string(),
-                                serde_json::Value::Number(
-                                    serde_json::Number::from_f64(num).unwrap(),
-                                ),
-                            );
-                        } else {
-                            facets.insert(facet_key.to_string(), serde_json::Value::String(value));
-                        }
-                    }
-                }
+            let agent = agent_pool.checkout().await;
+            let pool_for_fallback = agent_pool.clone();
+            let req_for_agent = req.clone();
+            let embedding_for_agent = embedding.clone();
+            let (agent, facets, trace) = tokio::task::spawn_blocking(move || {
+                run_agent_pass(agent, &req_for_agent, embedding_for_agent, facets)
+            })
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Agent worker task panicked: {:?}", e);
+                (pool_for_fallback.fresh_agent(), HashMap::new(), Vec::new())
+            });
+            agent_pool.checkin(agent).await;
 
-                // Note: facets.* keys are already processed above
-
-                // Fallback/augment: map any remaining percept.* keys the agent left in memory
-                {
-                    if let Some(obj) = short_mem.get("percept.vision.object") {
-                        facets
-                            .entry("vision.object".into())
-                            .or_insert_with(|| serde_json::Value::String(obj.clone()));
-                    }
-                    if let Some(col) = short_mem.get("percept.vision.color") {
-                        facets
-                            .entry("color.dominant".into())
-                            .or_insert_with(|| serde_json::Value::String(col.clone()));
-                    }
-                    if let Some(tr) = short_mem.get("percept.speech.transcript") {
-                        facets
-                            .entry("speech.transcript".into())
-                            .or_insert_with(|| serde_json::Value::String(tr.clone()));
-                    }
-                    if let Some(inten) = short_mem.get("percept.speech.intent") {
-                        facets
-                            .entry("speech.intent".into())
-                            .or_insert_with(|| serde_json::Value::String(inten.clone()));
-                    }
-                    if let Some(sent) = short_mem.get("percept.speech.sentiment") {
-                        facets
-                            .entry("speech.sentiment".into())
-                            .or_insert_with(|| serde_json::Value::String(sent.clone()));
-                    }
-                    if let Some(v) = short_mem.get("percept.affect.valence") {
-                        if let Ok(num) = v.parse::<f64>() {
-                            facets
-                                .entry("affect.valence".into())
-                                .or_insert(serde_json::json!(num));
-                        }
-                    }
-                    if let Some(a) = short_mem.get("percept.affect.arousal") {
-                        if let Ok(num) = a.parse::<f64>() {
-                            facets
-                                .entry("affect.arousal".into())
-                                .or_insert(serde_json::json!(num));
-                        }
-                    }
-                }
-            }
+            let (facets, facet_errors) = normalize_facets(facets);
+            let content_hash = EventHash::of(&facets);
 
             let token = SentienceToken {
                 event_type: "sentience.token".to_string(),
@@ -466,6 +1400,9 @@ agent MultiModalAnalyzer {
                     .unwrap_or("unknown")
                     .to_string(),
                 facets: facets.clone(),
+                trace,
+                content_hash: content_hash.clone(),
+                facet_errors,
             };
 
             // Determine source type based on input
@@ -475,157 +1412,46 @@ agent MultiModalAnalyzer {
                 "vision"
             };
 
-            // Add to memory
-            let embedding = req.get("embedding")
-                .and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
-                .unwrap_or_default();
-            
+            // Add to memory (seq is assigned by add_to_memory at insert time)
             let memory_event = MemoryEvent {
+                seq: 0,
                 ts: timestamp,
                 embedding_id: token.embedding_id.clone(),
                 embedding: embedding,
                 facets: facets,
                 source: source.to_string(),
+                content_hash,
             };
             add_to_memory(memory_event);
 
-            warp::reply::json(&token)
+            Ok::<_, std::convert::Infallible>(warp::reply::json(&token))
+            }
         });
 
+    let tokenize_agent_pool = agent_pool.clone();
     let tokenize = warp::path("tokenize")
         .and(warp::post())
         .and(warp::body::json())
-        .map(|req: TokenizeRequest| {
+        .and_then(move |req: TokenizeRequest| {
+            let agent_pool = tokenize_agent_pool.clone();
+            async move {
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
 
-            let mut facets = HashMap::new();
-
-            // Use real Sentience agent to analyze input
-            if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
-                // Build a DSL snippet that seeds percept.* keys the agent expects.
-                // Vision (pick top-1 label if present)
-                let mut top_label: Option<String> = None;
-                if let Some(clip_topk) = &req.clip_topk {
-                    if let Some(first) = clip_topk.first() {
-                        top_label = Some(first.label.clone());
-                    }
-                }
-
-                // Heuristic color from label (demo only)
-                let color = match top_label.as_deref() {
-                    Some("banana") => "yellow",
-                    Some("apple")  => "red",
-                    _ => "unknown",
-                };
-
-                let t = req.transcript.as_deref().unwrap_or("");
-                let t_esc = escape_dsl(t);
-                let emb_esc = escape_dsl(&req.embedding_id);
-
-                // Tiny default intent/sentiment; upstream can get smarter later.
-                let lower = t.to_lowercase();
-                let mut intent = "statement";
-                let sentiment = "neutral";
-                if !t.is_empty() {
-                    if lower.contains('?') || lower.starts_with("what ") || lower.starts_with("who ")
-                        || lower.starts_with("why ") || lower.starts_with("how ")
-                        || lower.starts_with("when ") || lower.starts_with("where ") {
-                        intent = "question";
-                    } else if lower.starts_with("hello") || lower.starts_with("hi") {
-                        intent = "greeting";
-                    }
-                }
-
-                let dsl = format!(
-                    ".use \"MultiModalWriter\"\n\
-                     {vision_obj}{vision_color}mem.short[\"percept.vision.embedding_id\"] = \"{emb}\"\n\
-                     mem.short[\"percept.speech.transcript\"] = \"{t}\"\n\
-                     mem.short[\"percept.speech.intent\"] = \"{intent}\"\n\
-                     mem.short[\"percept.speech.sentiment\"] = \"{sentiment}\"\n\
-                     mem.short[\"percept.affect.valence\"] = 0.5\n\
-                     mem.short[\"percept.affect.arousal\"] = 0.3\n\
-                     .input \"tick\"",
-                    emb = emb_esc,
-                    t = t_esc,
-                    intent = intent,
-                    sentiment = sentiment,
-                    vision_obj = if let Some(lbl) = &top_label {
-                        format!("mem.short[\"percept.vision.object\"] = \"{}\"\n", escape_dsl(lbl))
-                    } else { String::new() },
-                    vision_color = if top_label.is_some() {
-                        format!("mem.short[\"percept.vision.color\"] = \"{}\"\n", color)
-                    } else { String::new() },
-                );
-
-                println!("Sending to Sentience via DSL script:\n{}", dsl);
-                let _ = agent.run_sentience(&dsl);
-
-                // Debug: print all memory
-                let short_mem = agent.all_short();
-                println!("Short memory: {:?}", short_mem);
-
-                // Extract facets from agent's short-term memory
-                for (key, value) in short_mem.clone() {
-                    if key.starts_with("vision")
-                        || key.starts_with("speech")
-                        || key.starts_with("affect")
-                        || key.starts_with("color")
-                    {
-                        if let Ok(num) = value.parse::<f64>() {
-                            facets.insert(
-                                key,
-                                serde_json::Value::Number(serde_json::Number::from_f64(num).unwrap()),
-                            );
-                        } else {
-                            facets.insert(key, serde_json::Value::String(value));
-                        }
-                    }
-                }
-
-                // Merge explicit facets.* keys written by the agent
-                for (k, v) in short_mem.iter() {
-                    if let Some(stripped) = k.strip_prefix("facets.") {
-                        if let Ok(num) = v.parse::<f64>() {
-                            facets.insert(stripped.to_string(), serde_json::json!(num));
-                        } else {
-                            facets.insert(stripped.to_string(), serde_json::Value::String(v.clone()));
-                        }
-                    }
-                }
-
-                // Fallback/augment: map any remaining percept.* keys the agent left in memory
-                {
-                    if let Some(obj) = short_mem.get("percept.vision.object") {
-                        facets.entry("vision.object".into()).or_insert_with(|| serde_json::Value::String(obj.clone()));
-                    }
-                    if let Some(col) = short_mem.get("percept.vision.color") {
-                        facets.entry("color.dominant".into()).or_insert_with(|| serde_json::Value::String(col.clone()));
-                    }
-                    if let Some(tr) = short_mem.get("percept.speech.transcript") {
-                        facets.entry("speech.transcript".into()).or_insert_with(|| serde_json::Value::String(tr.clone()));
-                    }
-                    if let Some(inten) = short_mem.get("percept.speech.intent") {
-                        facets.entry("speech.intent".into()).or_insert_with(|| serde_json::Value::String(inten.clone()));
-                    }
-                    if let Some(sent) = short_mem.get("percept.speech.sentiment") {
-                        facets.entry("speech.sentiment".into()).or_insert_with(|| serde_json::Value::String(sent.clone()));
-                    }
-                    if let Some(v) = short_mem.get("percept.affect.valence") {
-                        if let Ok(num) = v.parse::<f64>() {
-                            facets.entry("affect.valence".into()).or_insert(serde_json::json!(num));
-                        }
-                    }
-                    if let Some(a) = short_mem.get("percept.affect.arousal") {
-                        if let Ok(num) = a.parse::<f64>() {
-                            facets.entry("affect.arousal".into()).or_insert(serde_json::json!(num));
-                        }
-                    }
-                }
-            }
+            let agent = agent_pool.checkout().await;
+            let pool_for_fallback = agent_pool.clone();
+            let req_for_agent = req.clone();
+            let (agent, mut facets, trace) = tokio::task::spawn_blocking(move || {
+                run_tokenize_pass(agent, &req_for_agent)
+            })
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Agent worker task panicked: {:?}", e);
+                (pool_for_fallback.fresh_agent(), HashMap::new(), Vec::new())
+            });
+            agent_pool.checkin(agent).await;
 
             // Ensure we always have something meaningful for the UI (augment, don't overwrite)
             {
@@ -646,62 +1472,235 @@ agent MultiModalAnalyzer {
                 }
             }
 
+            let (facets, facet_errors) = normalize_facets(facets);
+
             let token = SentienceToken {
                 event_type: "sentience.token".to_string(),
                 ts: timestamp,
                 embedding_id: req.embedding_id,
+                content_hash: EventHash::of(&facets),
                 facets,
+                trace,
+                facet_errors,
             };
 
-            warp::reply::json(&token)
+            Ok::<_, std::convert::Infallible>(warp::reply::json(&token))
+            }
         });
 
+    // Pushes limit/since_ts/hash down into an indexed SQL query against
+    // MEMORY_DB instead of loading and sorting the whole in-memory cache,
+    // so this stays cheap as the store grows well past MEMORY_STORE's
+    // MEMORY_CAPACITY window.
     let memory = warp::path("memory")
         .and(warp::get())
         .and(warp::query::<HashMap<String, String>>())
         .map(|params: HashMap<String, String>| {
             let limit = params.get("limit")
-                .and_then(|s| s.parse::<usize>().ok())
+                .and_then(|s| s.parse::<i64>().ok())
                 .unwrap_or(200);
             let since_ts = params.get("since_ts")
-                .and_then(|s| s.parse::<u64>().ok());
+                .and_then(|s| s.parse::<i64>().ok());
+            let hash = params.get("hash");
 
-            if let Ok(memory) = MEMORY_STORE.lock() {
-                let mut events: Vec<&MemoryEvent> = memory.iter().collect();
-                
-                // Filter by timestamp if provided
-                if let Some(since) = since_ts {
-                    events.retain(|e| e.ts >= since);
+            // used/capacity/remaining describe MEMORY_STORE (the bounded
+            // active window), not MEMORY_DB's full row count - MEMORY_DB is
+            // intentionally unbounded, so it has no "remaining" to report.
+            let used = MEMORY_STORE.lock().map(|m| m.len()).unwrap_or(0);
+            let capacity = *MEMORY_CAPACITY;
+            let remaining = capacity.saturating_sub(used);
+
+            let db = match MEMORY_DB.lock() {
+                Ok(db) => db,
+                Err(_) => {
+                    return warp::reply::json(&MemoryPageResponse {
+                        events: Vec::new(),
+                        used,
+                        capacity,
+                        remaining,
+                    })
                 }
-                
-                // Sort by timestamp (newest first)
-                events.sort_by(|a, b| b.ts.cmp(&a.ts));
-                
-                // Apply limit
-                events.truncate(limit);
-                
-                warp::reply::json(&events)
+            };
+
+            // A content_hash lookup identifies one stable event and ignores
+            // the other filters/limit - it's a point query, not a range one.
+            let mut sql = "SELECT * FROM memory_events WHERE 1=1".to_string();
+            let mut sql_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+            if let Some(hash) = hash {
+                sql.push_str(" AND content_hash = ?");
+                sql_params.push(Box::new(hash.clone()));
             } else {
-                warp::reply::json(&Vec::<&MemoryEvent>::new())
+                if let Some(since) = since_ts {
+                    sql.push_str(" AND ts >= ?");
+                    sql_params.push(Box::new(since));
+                }
+                sql.push_str(" ORDER BY ts DESC LIMIT ?");
+                sql_params.push(Box::new(limit));
             }
+
+            let mut stmt = match db.prepare(&sql) {
+                Ok(stmt) => stmt,
+                Err(e) => {
+                    eprintln!("Failed to query data/memory.db: {}", e);
+                    return warp::reply::json(&MemoryPageResponse {
+                        events: Vec::new(),
+                        used,
+                        capacity,
+                        remaining,
+                    });
+                }
+            };
+            let param_refs: Vec<&dyn rusqlite::ToSql> =
+                sql_params.iter().map(|p| p.as_ref()).collect();
+            let events: Vec<MemoryEvent> = match stmt.query_map(param_refs.as_slice(), row_to_memory_event) {
+                Ok(rows) => rows.filter_map(Result::ok).collect(),
+                Err(e) => {
+                    eprintln!("Failed to read data/memory.db rows: {}", e);
+                    Vec::new()
+                }
+            };
+
+            warp::reply::json(&MemoryPageResponse {
+                events,
+                used,
+                capacity,
+                remaining,
+            })
         });
 
     let memory_stream = warp::path("memory")
         .and(warp::path("stream"))
         .and(warp::get())
-        .map(|| {
-            // For now, return a simple SSE stream
-            // In a real implementation, this would be a proper SSE stream
-            warp::reply::with_header(
-                "data: {\"type\":\"memory.stream\",\"message\":\"Memory stream started\"}\n\n",
-                "content-type",
-                "text/event-stream"
-            )
+        .and(warp::header::optional::<String>("last-event-id"))
+        .and(warp::query::<MemoryStreamQuery>())
+        .map(|last_event_id: Option<String>, query: MemoryStreamQuery| {
+            // A `Last-Event-ID` header (set automatically by EventSource on
+            // reconnect) takes priority over `?since_ts=`, which a
+            // first-time client can use to ask for recent history before
+            // the live stream starts.
+            let last_seq = last_event_id.and_then(|s| s.parse::<u64>().ok());
+
+            // Subscribe before reading the buffer so no event pushed in
+            // between the two can fall in the gap and be lost.
+            let receiver = MEMORY_BROADCAST.subscribe();
+
+            let replay: Vec<Result<warp::sse::Event, std::convert::Infallible>> =
+                match MEMORY_STORE.lock() {
+                    Ok(memory) => memory
+                        .iter()
+                        .filter(|e| match (last_seq, query.since_ts) {
+                            (Some(since_seq), _) => e.seq > since_seq,
+                            (None, Some(since_ts)) => e.ts >= since_ts,
+                            (None, None) => false,
+                        })
+                        .map(|e| Ok(memory_event_to_sse(e)))
+                        .collect(),
+                    Err(_) => Vec::new(),
+                };
+
+            let live = tokio_stream::wrappers::BroadcastStream::new(receiver).map(|item| {
+                Ok::<_, std::convert::Infallible>(match item {
+                    Ok(MemoryStreamMessage::Event(event)) => memory_event_to_sse(&event),
+                    Ok(MemoryStreamMessage::Evicted { seq, content_hash, ts }) => {
+                        let payload = serde_json::json!({
+                            "seq": seq,
+                            "content_hash": content_hash,
+                            "ts": ts,
+                        })
+                        .to_string();
+                        warp::sse::Event::default().event("memory.evicted").data(payload)
+                    }
+                    Err(tokio_stream::wrappers::errors::BroadcastStreamRecvError::Lagged(n)) => {
+                        let payload = serde_json::json!({
+                            "missed": n,
+                            "hint": "re-fetch via /memory",
+                        })
+                        .to_string();
+                        warp::sse::Event::default().event("memory.gap").data(payload)
+                    }
+                })
+            });
+
+            warp::sse::reply(warp::sse::keep_alive().stream(futures::stream::iter(replay).chain(live)))
+        });
+
+    // Two-stage recall: cosine-similarity shortlist over every stored
+    // embedding, then rerank the shortlist by similarity + recency decay +
+    // facet overlap with the caller's current context. Turns the ring
+    // buffer into a queryable episodic memory instead of write-only.
+    let recall = warp::path("recall")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(|req: RecallRequest| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            let top_k = req.top_k.unwrap_or(8).max(1);
+            let candidate_pool = top_k * 4;
+
+            let memory = match MEMORY_STORE.lock() {
+                Ok(m) => m,
+                Err(_) => return warp::reply::json(&Vec::<serde_json::Value>::new()),
+            };
+
+            // Stage 1: shortlist by cosine similarity alone.
+            let mut candidates: Vec<(&MemoryEvent, f64)> = memory
+                .iter()
+                .filter_map(|event| {
+                    cosine_similarity(&req.embedding, &event.embedding).map(|sim| (event, sim))
+                })
+                .collect();
+            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            candidates.truncate(candidate_pool);
+
+            // Stage 2: rerank the shortlist by a blended score.
+            let mut hits: Vec<RecallHit> = candidates
+                .into_iter()
+                .map(|(event, similarity)| {
+                    let age_secs = now.saturating_sub(event.ts) as f64;
+                    let recency = (-RECALL_RECENCY_LAMBDA * age_secs).exp();
+                    let overlap = facet_overlap(&req.facets, &event.facets) as f64;
+                    let score = RECALL_SIMILARITY_WEIGHT * similarity
+                        + RECALL_RECENCY_WEIGHT * recency
+                        + RECALL_FACET_WEIGHT * overlap;
+                    RecallHit { event, score }
+                })
+                .collect();
+            hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+            hits.truncate(top_k);
+
+            warp::reply::json(&hits)
+        });
+
+    // Registers a remote dataspace/subscriber that wants the sentience
+    // token feed pushed to it over HTTP instead of polling /memory or
+    // holding open an SSE connection. Each registration spawns its own
+    // long-lived relay task; there is no unregister endpoint, so a
+    // registered relay runs until the process exits.
+    let relay = warp::path("relay")
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(|reg: RelayRegistration| {
+            spawn_relay_task(reg.url.clone());
+            warp::reply::json(&serde_json::json!({
+                "status": "registered",
+                "url": reg.url,
+            }))
         });
 
     let root = warp::path::end().map(|| "I am Sentience service");
 
-    let routes = ping.or(healthz).or(run).or(tokenize).or(memory).or(memory_stream).or(root).with(cors);
+    let routes = ping
+        .or(healthz)
+        .or(run)
+        .or(tokenize)
+        .or(memory)
+        .or(memory_stream)
+        .or(recall)
+        .or(relay)
+        .or(root)
+        .with(cors);
 
     warp::serve(routes).run(([0, 0, 0, 0], 8082)).await;
 }
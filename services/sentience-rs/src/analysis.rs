@@ -0,0 +1,206 @@
+//! Text analysis shared by `/run` and `/tokenize`, extracted from the
+//! duplicated `starts_with`/`contains` heuristics that used to live inline
+//! in each handler. `TextAnalyzer` is the extension point; `LexiconAnalyzer`
+//! is the default implementation, scoring affect from a term -> (valence,
+//! arousal) lexicon instead of a handful of hardcoded words, and picking
+//! intent from an ordered list of rules instead of a chain of `if`s.
+
+use std::collections::HashMap;
+
+/// Recognized communicative intents. `as_str` gives the exact string
+/// written into the `speech.intent` facet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Question,
+    Greeting,
+    Statement,
+}
+
+impl Intent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Intent::Question => "question",
+            Intent::Greeting => "greeting",
+            Intent::Statement => "statement",
+        }
+    }
+}
+
+/// Maps raw transcript text to an intent and an affect reading. Lives
+/// behind a trait so the lexicon-backed default can be swapped for
+/// something smarter (a model call, a different language's lexicon) without
+/// touching the `/run`/`/tokenize` handlers.
+pub trait TextAnalyzer: Send + Sync {
+    fn intent(&self, text: &str) -> Intent;
+
+    /// `(valence, arousal)`, both on the service's existing `0.0..=1.0`
+    /// facet scale (0.5 valence / 0.3 arousal is the neutral baseline the
+    /// handlers used before this analyzer existed).
+    fn affect(&self, text: &str) -> (f64, f64);
+}
+
+/// One intent-matching rule, tried in order; the first match wins.
+#[derive(Debug, Clone)]
+pub struct IntentRule {
+    pub intent: Intent,
+    /// Matches if the lowercased text contains any of these substrings...
+    pub contains: Vec<&'static str>,
+    /// ...or starts with any of these prefixes.
+    pub starts_with: Vec<&'static str>,
+}
+
+impl IntentRule {
+    fn matches(&self, lower: &str) -> bool {
+        self.contains.iter().any(|s| lower.contains(s))
+            || self.starts_with.iter().any(|s| lower.starts_with(s))
+    }
+}
+
+fn default_intent_rules() -> Vec<IntentRule> {
+    vec![
+        IntentRule {
+            intent: Intent::Question,
+            contains: vec!["?"],
+            starts_with: vec!["what ", "who ", "why ", "how ", "when ", "where "],
+        },
+        IntentRule {
+            intent: Intent::Greeting,
+            contains: vec![],
+            starts_with: vec!["hello", "hi"],
+        },
+    ]
+}
+
+/// Built-in term -> (valence, arousal) weights. Valence is signed
+/// (negative..positive); `affect` remaps it onto the `0.0..=1.0` facet
+/// scale. Deliberately small — swap in a real lexicon file via
+/// `LexiconAnalyzer::load` for anything beyond a demo default.
+fn default_lexicon() -> HashMap<&'static str, (f64, f64)> {
+    HashMap::from([
+        ("good", (0.6, 0.4)),
+        ("great", (0.8, 0.5)),
+        ("awesome", (0.9, 0.6)),
+        ("love", (0.8, 0.6)),
+        ("happy", (0.7, 0.5)),
+        ("excited", (0.7, 0.8)),
+        ("calm", (0.3, 0.1)),
+        ("bad", (-0.6, 0.4)),
+        ("terrible", (-0.9, 0.6)),
+        ("hate", (-0.8, 0.6)),
+        ("sad", (-0.6, 0.3)),
+        ("angry", (-0.7, 0.8)),
+        ("afraid", (-0.6, 0.7)),
+    ])
+}
+
+/// Default `TextAnalyzer`: intent from `IntentRule`s, affect summed and
+/// averaged over lexicon-matched tokens.
+pub struct LexiconAnalyzer {
+    lexicon: HashMap<String, (f64, f64)>,
+    intent_rules: Vec<IntentRule>,
+}
+
+impl LexiconAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            lexicon: default_lexicon()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            intent_rules: default_intent_rules(),
+        }
+    }
+
+    /// Load a lexicon from `path`: one `term,valence,arousal` CSV line per
+    /// entry (`#`-prefixed lines and blanks are skipped). Falls back to the
+    /// built-in lexicon if the file is missing, unreadable, or empty, so a
+    /// bad config path degrades rather than crashing the service.
+    pub fn load(path: &str) -> Self {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!(
+                    "Could not read lexicon file {:?}: {}; using built-in lexicon",
+                    path, e
+                );
+                return Self::new();
+            }
+        };
+
+        let mut lexicon = HashMap::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(',');
+            let (Some(term), Some(valence), Some(arousal)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if let (Ok(valence), Ok(arousal)) =
+                (valence.trim().parse::<f64>(), arousal.trim().parse::<f64>())
+            {
+                lexicon.insert(term.trim().to_lowercase(), (valence, arousal));
+            }
+        }
+
+        if lexicon.is_empty() {
+            eprintln!(
+                "Lexicon file {:?} had no usable entries; using built-in lexicon",
+                path
+            );
+            return Self::new();
+        }
+
+        Self {
+            lexicon,
+            intent_rules: default_intent_rules(),
+        }
+    }
+}
+
+impl Default for LexiconAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextAnalyzer for LexiconAnalyzer {
+    fn intent(&self, text: &str) -> Intent {
+        if text.is_empty() {
+            return Intent::Statement;
+        }
+        let lower = text.to_lowercase();
+        self.intent_rules
+            .iter()
+            .find(|rule| rule.matches(&lower))
+            .map(|rule| rule.intent)
+            .unwrap_or(Intent::Statement)
+    }
+
+    fn affect(&self, text: &str) -> (f64, f64) {
+        let lower = text.to_lowercase();
+        let mut valence_sum = 0.0;
+        let mut arousal_sum = 0.0;
+        let mut matched = 0u32;
+
+        for token in lower.split_whitespace() {
+            let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+            if let Some((valence, arousal)) = self.lexicon.get(token) {
+                valence_sum += valence;
+                arousal_sum += arousal;
+                matched += 1;
+            }
+        }
+
+        if matched == 0 {
+            return (0.5, 0.3);
+        }
+
+        let valence = (valence_sum / matched as f64).clamp(-1.0, 1.0);
+        let arousal = (arousal_sum / matched as f64).clamp(0.0, 1.0);
+        ((valence + 1.0) / 2.0, arousal)
+    }
+}
@@ -0,0 +1,126 @@
+//! In-process publish/subscribe relay for `SentienceToken`s, inspired by
+//! dataspace-style external protocols: every token produced by `/run` or
+//! `/tokenize` is published here in addition to being returned in the HTTP
+//! response, and `/subscribe` streams matching tokens to connected clients
+//! as they're produced instead of making the UI poll.
+
+use crate::SentienceToken;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Bus capacity: how many events a slow subscriber can fall behind by
+/// before `broadcast` starts dropping the oldest for it.
+const BUS_CAPACITY: usize = 256;
+
+/// An assertion or retraction flowing over the bus. Tagged so subscribers
+/// can tell the two apart in the SSE payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event")]
+pub enum BusEvent {
+    /// A freshly produced token.
+    Assert { token: SentienceToken },
+    /// The token previously published for `embedding_id` no longer holds —
+    /// a newer one has superseded it. Carries the superseded facets so a
+    /// subscriber's filter can decide whether it cares about the retraction
+    /// without having cached the original assertion itself.
+    Retract {
+        embedding_id: String,
+        facets: HashMap<String, serde_json::Value>,
+    },
+}
+
+/// A single interest clause: `key` must be present, and if `value` is set,
+/// the facet's value must stringify to exactly `value`.
+#[derive(Debug, Clone)]
+pub struct FacetFilter {
+    key: String,
+    value: Option<String>,
+}
+
+/// Parse a comma-separated filter spec, e.g. `"speech.intent=question,vision.object"`,
+/// into clauses ANDed together. An empty spec matches everything.
+pub fn parse_filters(spec: &str) -> Vec<FacetFilter> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|clause| !clause.is_empty())
+        .map(|clause| match clause.split_once('=') {
+            Some((key, value)) => FacetFilter {
+                key: key.trim().to_string(),
+                value: Some(value.trim().to_string()),
+            },
+            None => FacetFilter {
+                key: clause.to_string(),
+                value: None,
+            },
+        })
+        .collect()
+}
+
+fn facets_match(facets: &HashMap<String, serde_json::Value>, filters: &[FacetFilter]) -> bool {
+    filters.iter().all(|filter| match facets.get(&filter.key) {
+        None => false,
+        Some(v) => match &filter.value {
+            None => true,
+            Some(expected) => match v.as_str() {
+                Some(s) => s == expected,
+                None => v.to_string() == *expected,
+            },
+        },
+    })
+}
+
+/// Does `event` satisfy every clause in `filters`?
+pub fn event_matches(event: &BusEvent, filters: &[FacetFilter]) -> bool {
+    match event {
+        BusEvent::Assert { token } => facets_match(&token.facets, filters),
+        BusEvent::Retract { facets, .. } => facets_match(facets, filters),
+    }
+}
+
+/// Publish/subscribe relay plus the bookkeeping needed to emit retractions
+/// when an `embedding_id` is republished.
+pub struct TokenBus {
+    sender: broadcast::Sender<BusEvent>,
+    latest: Mutex<HashMap<String, SentienceToken>>,
+}
+
+impl TokenBus {
+    pub fn new() -> Self {
+        let (sender, _receiver) = broadcast::channel(BUS_CAPACITY);
+        Self {
+            sender,
+            latest: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Publish `token`, first retracting whatever was previously asserted
+    /// for the same `embedding_id`, if anything.
+    pub fn publish(&self, token: SentienceToken) {
+        let previous = self
+            .latest
+            .lock()
+            .unwrap()
+            .insert(token.embedding_id.clone(), token.clone());
+
+        if let Some(previous) = previous {
+            let _ = self.sender.send(BusEvent::Retract {
+                embedding_id: previous.embedding_id,
+                facets: previous.facets,
+            });
+        }
+
+        let _ = self.sender.send(BusEvent::Assert { token });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for TokenBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
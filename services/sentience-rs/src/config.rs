@@ -0,0 +1,70 @@
+//! Layered configuration for the Sentience service: `Default`, then an
+//! optional `config.toml`, then `LJ_*` environment overrides, mirroring
+//! ego-rs's config loader so operators can reconfigure either service
+//! without a rebuild.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_bind_addr")]
+    pub bind_addr: String,
+    #[serde(default = "default_agent_path")]
+    pub agent_path: String,
+    /// Path to a custom `term,valence,arousal` lexicon file for
+    /// `LexiconAnalyzer`. Unset uses the built-in lexicon.
+    #[serde(default)]
+    pub lexicon_path: Option<String>,
+}
+
+fn default_bind_addr() -> String {
+    "0.0.0.0:8082".to_string()
+}
+
+fn default_agent_path() -> String {
+    "agent.sentience".to_string()
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_addr: default_bind_addr(),
+            agent_path: default_agent_path(),
+            lexicon_path: None,
+        }
+    }
+}
+
+impl Config {
+    /// Layer configuration lowest-to-highest precedence: `Default`, an
+    /// optional `config.toml` in the working directory, then `LJ_*`
+    /// environment variables.
+    pub fn load() -> Self {
+        let mut config = Self::from_toml_file("config.toml").unwrap_or_default();
+        config.apply_env_overrides();
+        config
+    }
+
+    fn from_toml_file(path: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        match toml::from_str(&contents) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("Failed to parse {}: {}; using defaults", path, e);
+                None
+            }
+        }
+    }
+
+    fn apply_env_overrides(&mut self) {
+        if let Ok(v) = std::env::var("LJ_BIND_ADDR") {
+            self.bind_addr = v;
+        }
+        if let Ok(v) = std::env::var("LJ_AGENT_PATH") {
+            self.agent_path = v;
+        }
+        if let Ok(v) = std::env::var("LJ_LEXICON_PATH") {
+            self.lexicon_path = Some(v);
+        }
+    }
+}
@@ -1,3 +1,11 @@
+mod analysis;
+mod bus;
+mod config;
+
+use analysis::{LexiconAnalyzer, TextAnalyzer};
+use bus::{BusEvent, TokenBus};
+use config::Config;
+use futures::StreamExt;
 use sentience::SentienceAgent;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,8 +26,8 @@ struct ClipItem {
     score: f64,
 }
 
-#[derive(Serialize)]
-struct SentienceToken {
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SentienceToken {
     #[serde(rename = "type")]
     event_type: String,
     ts: u64,
@@ -27,31 +35,103 @@ struct SentienceToken {
     facets: HashMap<String, serde_json::Value>,
 }
 
-// Global Sentience agent instance
 use std::sync::Arc;
-use std::sync::Mutex;
 
-lazy_static::lazy_static! {
-    static ref SENTIENCE_AGENT: Arc<Mutex<SentienceAgent>> = Arc::new(Mutex::new(SentienceAgent::new()));
+/// Bounded pool of pre-initialized `SentienceAgent`s, sized to the number of
+/// CPUs. Replaces a single shared `Arc<Mutex<SentienceAgent>>` so `/run` and
+/// `/tokenize` requests no longer serialize on one lock and block the whole
+/// server under concurrent load. Each handler checks out an idle agent, runs
+/// its DSL, and returns the agent to the pool — agents are never shared
+/// concurrently, so one request's `mem.short` can't leak into another's.
+struct AgentPool {
+    idle: crossbeam_channel::Receiver<SentienceAgent>,
+    release: crossbeam_channel::Sender<SentienceAgent>,
 }
 
+impl AgentPool {
+    /// Build `size` agents, each loaded from `agent_code`, and fill the pool.
+    fn new(size: usize, agent_code: &str) -> Self {
+        let (release, idle) = crossbeam_channel::bounded(size);
+        for _ in 0..size {
+            let mut agent = SentienceAgent::new();
+            let _ = agent.run_sentience(agent_code);
+            release
+                .send(agent)
+                .expect("pool channel has capacity for every agent it creates");
+        }
+        Self { idle, release }
+    }
+
+    /// Block until an agent is free, give `f` exclusive access to it, then
+    /// return it to the pool.
+    fn with_agent<R>(&self, f: impl FnOnce(&mut SentienceAgent) -> R) -> R {
+        let mut agent = self
+            .idle
+            .recv()
+            .expect("pool sender outlives all receivers");
+        let result = f(&mut agent);
+        let _ = self.release.send(agent);
+        result
+    }
+}
+
+fn with_pool(
+    pool: Arc<AgentPool>,
+) -> impl Filter<Extract = (Arc<AgentPool>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || pool.clone())
+}
+
+fn with_bus(
+    bus: Arc<TokenBus>,
+) -> impl Filter<Extract = (Arc<TokenBus>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || bus.clone())
+}
+
+fn with_analyzer(
+    analyzer: Arc<dyn TextAnalyzer>,
+) -> impl Filter<Extract = (Arc<dyn TextAnalyzer>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || analyzer.clone())
+}
+
+/// Query params accepted by `GET /subscribe`: a comma-separated list of
+/// facet-interest clauses, e.g. `?facets=speech.intent=question,vision.object`.
+#[derive(Deserialize)]
+struct SubscribeQuery {
+    #[serde(default)]
+    facets: String,
+}
+
+// Sanitize untrusted text before it's interpolated into a DSL script line.
+// The whole line is executed by `agent.run_sentience`, so a transcript
+// containing a raw `\n` could close the current statement and inject new
+// directives. It drops every control character (including
+// `\n`, `\r`, `\t`) so no interpolated value can carry a line break, then
+// neutralizes the directive tokens (`.use`, `.input`, `mem.short`) a payload
+// smuggled past that filter would need to start a new statement, before
+// finally escaping the quote the result is interpolated into.
 fn escape_dsl(s: &str) -> String {
-    s.replace('\\', "\\\\").replace('"', "\\\"")
+    let sanitized: String = s.chars().filter(|c| !c.is_control()).collect();
+    let sanitized = sanitized
+        .replace(".use", "_use")
+        .replace(".input", "_input")
+        .replace("mem.short", "mem_short");
+    sanitized.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
 #[tokio::main]
 async fn main() {
-    println!("Sentience service starting on :8082");
+    let config = Config::load();
+    println!("Sentience service starting on {}", config.bind_addr);
     println!("I am Sentience service");
 
     // Load the Sentience agent from file
-    let agent_code = match fs::read_to_string("agent.sentience") {
+    let agent_code = match fs::read_to_string(&config.agent_path) {
         Ok(code) => {
-            println!("Loaded agent from agent.sentience");
+            println!("Loaded agent from {}", config.agent_path);
             code
         }
         Err(e) => {
-            eprintln!("Failed to read agent.sentience: {}", e);
+            eprintln!("Failed to read {}: {}", config.agent_path, e);
             eprintln!("Using fallback agent code");
             r#"
 agent MultiModalAnalyzer {
@@ -78,11 +158,22 @@ agent MultiModalAnalyzer {
         }
     };
 
-    // Register the agent
-    if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
-        let _ = agent.run_sentience(&agent_code);
-        println!("Sentience agent registered from agent.sentience");
-    }
+    // Build a pool of pre-initialized agents, one per CPU, so concurrent
+    // requests run in parallel instead of serializing on a single agent.
+    let pool_size = num_cpus::get().max(1);
+    let agent_pool = Arc::new(AgentPool::new(pool_size, &agent_code));
+    println!("Initialized {} Sentience agents in pool", pool_size);
+
+    // Publish/subscribe relay: every token `/run` and `/tokenize` produce is
+    // also broadcast here, so `/subscribe` can stream a live feed.
+    let token_bus = Arc::new(TokenBus::new());
+
+    // Shared intent/sentiment analyzer, backed by a custom lexicon file if
+    // one's configured, otherwise the built-in word list.
+    let analyzer: Arc<dyn TextAnalyzer> = match &config.lexicon_path {
+        Some(path) => Arc::new(LexiconAnalyzer::load(path)),
+        None => Arc::new(LexiconAnalyzer::new()),
+    };
 
     let cors = warp::cors()
         .allow_any_origin()
@@ -113,7 +204,10 @@ agent MultiModalAnalyzer {
     let run = warp::path("run")
         .and(warp::post())
         .and(warp::body::json())
-        .map(|req: serde_json::Value| {
+        .and(with_pool(agent_pool.clone()))
+        .and(with_bus(token_bus.clone()))
+        .and(with_analyzer(analyzer.clone()))
+        .map(|req: serde_json::Value, pool: Arc<AgentPool>, bus: Arc<TokenBus>, analyzer: Arc<dyn TextAnalyzer>| {
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -129,36 +223,18 @@ agent MultiModalAnalyzer {
                     serde_json::Value::String(t.to_string()),
                 );
 
-                // Lightweight intent/sentiment heuristics
-                let lower = t.to_lowercase();
-                let mut intent = "statement";
-                let mut sentiment = "neutral";
-                if !t.is_empty() {
-                    if lower.contains('?')
-                        || lower.starts_with("what ")
-                        || lower.starts_with("who ")
-                        || lower.starts_with("why ")
-                        || lower.starts_with("how ")
-                        || lower.starts_with("when ")
-                        || lower.starts_with("where ")
-                    {
-                        intent = "question";
-                    } else if lower.starts_with("hello") || lower.starts_with("hi") {
-                        intent = "greeting";
-                    }
-                    // very rough sentiment seed
-                    if lower.contains("bad") || lower.contains("terrible") {
-                        sentiment = "negative";
-                    } else if lower.contains("good")
-                        || lower.contains("great")
-                        || lower.contains("awesome")
-                    {
-                        sentiment = "positive";
-                    }
-                }
+                let intent = analyzer.intent(t);
+                let (valence, _arousal) = analyzer.affect(t);
+                let sentiment = if valence > 0.6 {
+                    "positive"
+                } else if valence < 0.4 {
+                    "negative"
+                } else {
+                    "neutral"
+                };
                 facets.insert(
                     "speech.intent".into(),
-                    serde_json::Value::String(intent.to_string()),
+                    serde_json::Value::String(intent.as_str().to_string()),
                 );
                 facets.insert(
                     "speech.sentiment".into(),
@@ -187,7 +263,7 @@ agent MultiModalAnalyzer {
             }
 
             // Use real Sentience agent to analyze input
-            if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
+            pool.with_agent(|agent| {
                 // Map incoming JSON to percept.* keys expected by the agent.
                 let transcript = req["transcript"].as_str().unwrap_or("");
                 let embedding_id = req["embedding_id"].as_str().unwrap_or("unknown");
@@ -196,24 +272,15 @@ agent MultiModalAnalyzer {
                 let t_esc = escape_dsl(transcript);
                 let ctx_esc = escape_dsl(ctx);
 
-                // Extremely lightweight intent/sentiment defaults (gateway may overwrite later).
-                let mut intent = "statement";
-                let sentiment = "neutral";
-                if !transcript.is_empty() {
-                    let lower = transcript.to_lowercase();
-                    if lower.contains('?')
-                        || lower.starts_with("what ")
-                        || lower.starts_with("who ")
-                        || lower.starts_with("why ")
-                        || lower.starts_with("how ")
-                        || lower.starts_with("when ")
-                        || lower.starts_with("where ")
-                    {
-                        intent = "question";
-                    } else if lower.starts_with("hello") || lower.starts_with("hi") {
-                        intent = "greeting";
-                    }
-                }
+                let intent = analyzer.intent(transcript).as_str();
+                let (valence, _arousal) = analyzer.affect(transcript);
+                let sentiment = if valence > 0.6 {
+                    "positive"
+                } else if valence < 0.4 {
+                    "negative"
+                } else {
+                    "neutral"
+                };
 
                 // Check if we have vision data
                 let vision_object = req.get("vision_object").and_then(|v| v.as_str()).unwrap_or("");
@@ -336,7 +403,7 @@ agent MultiModalAnalyzer {
                         }
                     }
                 }
-            }
+            });
 
             let token = SentienceToken {
                 event_type: "sentience.token".to_string(),
@@ -348,13 +415,17 @@ agent MultiModalAnalyzer {
                 facets,
             };
 
+            bus.publish(token.clone());
             warp::reply::json(&token)
         });
 
     let tokenize = warp::path("tokenize")
         .and(warp::post())
         .and(warp::body::json())
-        .map(|req: TokenizeRequest| {
+        .and(with_pool(agent_pool.clone()))
+        .and(with_bus(token_bus.clone()))
+        .and(with_analyzer(analyzer.clone()))
+        .map(|req: TokenizeRequest, pool: Arc<AgentPool>, bus: Arc<TokenBus>, analyzer: Arc<dyn TextAnalyzer>| {
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -363,7 +434,7 @@ agent MultiModalAnalyzer {
             let mut facets = HashMap::new();
 
             // Use real Sentience agent to analyze input
-            if let Ok(mut agent) = SENTIENCE_AGENT.lock() {
+            pool.with_agent(|agent| {
                 // Build a DSL snippet that seeds percept.* keys the agent expects.
                 // Vision (pick top-1 label if present)
                 let mut top_label: Option<String> = None;
@@ -384,19 +455,15 @@ agent MultiModalAnalyzer {
                 let t_esc = escape_dsl(t);
                 let emb_esc = escape_dsl(&req.embedding_id);
 
-                // Tiny default intent/sentiment; upstream can get smarter later.
-                let lower = t.to_lowercase();
-                let mut intent = "statement";
-                let sentiment = "neutral";
-                if !t.is_empty() {
-                    if lower.contains('?') || lower.starts_with("what ") || lower.starts_with("who ")
-                        || lower.starts_with("why ") || lower.starts_with("how ")
-                        || lower.starts_with("when ") || lower.starts_with("where ") {
-                        intent = "question";
-                    } else if lower.starts_with("hello") || lower.starts_with("hi") {
-                        intent = "greeting";
-                    }
-                }
+                let intent = analyzer.intent(t).as_str();
+                let (valence, _arousal) = analyzer.affect(t);
+                let sentiment = if valence > 0.6 {
+                    "positive"
+                } else if valence < 0.4 {
+                    "negative"
+                } else {
+                    "neutral"
+                };
 
                 let dsl = format!(
                     ".use \"MultiModalWriter\"\n\
@@ -483,7 +550,7 @@ agent MultiModalAnalyzer {
                         }
                     }
                 }
-            }
+            });
 
             // Ensure we always have something meaningful for the UI (augment, don't overwrite)
             {
@@ -511,12 +578,48 @@ agent MultiModalAnalyzer {
                 facets,
             };
 
+            bus.publish(token.clone());
             warp::reply::json(&token)
         });
 
-    let root = warp::path::end().map(|| "I am Sentience service");
+    let subscribe = warp::path("subscribe")
+        .and(warp::get())
+        .and(warp::query::<SubscribeQuery>())
+        .and(with_bus(token_bus.clone()))
+        .map(|query: SubscribeQuery, bus: Arc<TokenBus>| {
+            let filters = bus::parse_filters(&query.facets);
+            let receiver = bus.subscribe();
+            let stream = tokio_stream::wrappers::BroadcastStream::new(receiver).filter_map(
+                move |event| {
+                    let filters = filters.clone();
+                    async move {
+                        let event: BusEvent = event.ok()?;
+                        if !bus::event_matches(&event, &filters) {
+                            return None;
+                        }
+                        let data = serde_json::to_string(&event).ok()?;
+                        Some(Ok::<_, std::convert::Infallible>(
+                            warp::sse::Event::default().data(data),
+                        ))
+                    }
+                },
+            );
+            warp::sse::reply(warp::sse::keep_alive().stream(stream))
+        });
 
-    let routes = ping.or(healthz).or(run).or(tokenize).or(root).with(cors);
+    let root = warp::path::end().map(|| "I am Sentience service");
 
-    warp::serve(routes).run(([0, 0, 0, 0], 8082)).await;
+    let routes = ping
+        .or(healthz)
+        .or(run)
+        .or(tokenize)
+        .or(subscribe)
+        .or(root)
+        .with(cors);
+
+    let addr: std::net::SocketAddr = config
+        .bind_addr
+        .parse()
+        .unwrap_or_else(|e| panic!("Invalid bind_addr {:?}: {}", config.bind_addr, e));
+    warp::serve(routes).run(addr).await;
 }